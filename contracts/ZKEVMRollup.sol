@@ -13,36 +13,40 @@ interface ISP1Verifier {
     ) external view returns (bool);
 }
 
-interface IStateTransitionProof {
-    bytes32 oldStateRoot;
-    bytes32 newStateRoot;
-    uint256 batchIndex;
-    uint256 transactionCount;
-    bytes32[] transactionHashes;
-}
-
 contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
     ISP1Verifier public immutable verifier;
+    uint256 public immutable chainId;
     
     bytes32 public currentStateRoot;
     uint256 public currentBatchIndex;
+    /// Base fee the next batch must execute at, as the previous batch derived it.
+    uint256 public currentBaseFee;
     
     mapping(uint256 => Batch) public batches;
     mapping(bytes32 => bool) public processedProofs;
     
     struct Batch {
         bytes32 stateRoot;
-        bytes32[] transactionHashes;
+        bytes32 transactionsRoot;
+        bytes32 receiptsRoot;
+        bytes32 outcomesRoot;
+        uint256 transactionCount;
         uint256 timestamp;
         bool finalized;
     }
     
     struct StateTransition {
+        uint256 chainId;
         bytes32 oldStateRoot;
         bytes32 newStateRoot;
         uint256 batchIndex;
         uint256 transactionCount;
-        bytes32[] transactionHashes;
+        uint256 gasUsed;
+        uint256 baseFee;
+        uint256 nextBaseFee;
+        bytes32 transactionsRoot;
+        bytes32 receiptsRoot;
+        bytes32 outcomesRoot;
     }
     
     event BatchSubmitted(
@@ -68,14 +72,20 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
     error ProofAlreadyProcessed();
     error InvalidProof();
     error StateTransitionFailed();
+    error InvalidChainId();
+    error InvalidBaseFee();
     
     constructor(
         address _verifier,
-        bytes32 _initialStateRoot
+        bytes32 _initialStateRoot,
+        uint256 _chainId,
+        uint256 _initialBaseFee
     ) Ownable(msg.sender) {
         verifier = ISP1Verifier(_verifier);
+        chainId = _chainId;
         currentStateRoot = _initialStateRoot;
         currentBatchIndex = 0;
+        currentBaseFee = _initialBaseFee;
     }
     
     function submitBatch(
@@ -89,6 +99,10 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
             revert ProofAlreadyProcessed();
         }
         
+        if (transition.chainId != chainId) {
+            revert InvalidChainId();
+        }
+        
         if (transition.oldStateRoot != currentStateRoot) {
             revert InvalidStateRoot();
         }
@@ -97,6 +111,10 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
             revert InvalidBatchIndex();
         }
         
+        if (transition.baseFee != currentBaseFee) {
+            revert InvalidBaseFee();
+        }
+        
         bytes32 publicValuesHash = hashPublicValues(transition);
         
         bool isValid = verifier.verifyProof(proof, publicValuesHash, vKeyHash);
@@ -108,13 +126,17 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
         
         batches[transition.batchIndex] = Batch({
             stateRoot: transition.newStateRoot,
-            transactionHashes: transition.transactionHashes,
+            transactionsRoot: transition.transactionsRoot,
+            receiptsRoot: transition.receiptsRoot,
+            outcomesRoot: transition.outcomesRoot,
+            transactionCount: transition.transactionCount,
             timestamp: block.timestamp,
             finalized: true
         });
         
         currentStateRoot = transition.newStateRoot;
         currentBatchIndex++;
+        currentBaseFee = transition.nextBaseFee;
         
         emit BatchSubmitted(
             transition.batchIndex,
@@ -145,10 +167,15 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
         
         bytes32 expectedOldRoot = currentStateRoot;
         uint256 expectedBatchIndex = currentBatchIndex;
+        uint256 expectedBaseFee = currentBaseFee;
         
         for (uint256 i = 0; i < transitions.length; i++) {
             StateTransition calldata transition = transitions[i];
             
+            if (transition.chainId != chainId) {
+                revert InvalidChainId();
+            }
+            
             if (transition.oldStateRoot != expectedOldRoot) {
                 revert InvalidStateRoot();
             }
@@ -157,8 +184,13 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
                 revert InvalidBatchIndex();
             }
             
+            if (transition.baseFee != expectedBaseFee) {
+                revert InvalidBaseFee();
+            }
+            
             expectedOldRoot = transition.newStateRoot;
             expectedBatchIndex++;
+            expectedBaseFee = transition.nextBaseFee;
         }
         
         bytes32 publicValuesHash = hashAggregatedPublicValues(transitions);
@@ -175,7 +207,10 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
             
             batches[transition.batchIndex] = Batch({
                 stateRoot: transition.newStateRoot,
-                transactionHashes: transition.transactionHashes,
+                transactionsRoot: transition.transactionsRoot,
+                receiptsRoot: transition.receiptsRoot,
+                outcomesRoot: transition.outcomesRoot,
+                transactionCount: transition.transactionCount,
                 timestamp: block.timestamp,
                 finalized: true
             });
@@ -196,18 +231,27 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
         
         currentStateRoot = transitions[transitions.length - 1].newStateRoot;
         currentBatchIndex = expectedBatchIndex;
+        currentBaseFee = expectedBaseFee;
         
         emit ProofVerified(transitions[0].batchIndex, proofHash);
     }
     
+    /// Hash of the public values committed by the SP1 guest, which are exactly
+    /// this `abi.encode(...)`.
     function hashPublicValues(StateTransition calldata transition) public pure returns (bytes32) {
         return keccak256(
             abi.encode(
+                transition.chainId,
                 transition.oldStateRoot,
                 transition.newStateRoot,
                 transition.batchIndex,
                 transition.transactionCount,
-                keccak256(abi.encodePacked(transition.transactionHashes))
+                transition.gasUsed,
+                transition.baseFee,
+                transition.nextBaseFee,
+                transition.transactionsRoot,
+                transition.receiptsRoot,
+                transition.outcomesRoot
             )
         );
     }
@@ -222,6 +266,66 @@ contract ZKEVMRollup is Ownable, ReentrancyGuard, Pausable {
         return keccak256(abi.encodePacked(hashes));
     }
     
+    /// Checks that `transactionHash` is the `index`-th transaction of a batch,
+    /// against the binary keccak Merkle root the guest commits (leaves padded
+    /// with zero hashes to a power of two, siblings ordered leaf to root).
+    function verifyTransactionInclusion(
+        uint256 batchIndex,
+        bytes32 transactionHash,
+        uint256 index,
+        bytes32[] calldata proof
+    ) external view returns (bool) {
+        Batch storage batch = batches[batchIndex];
+        return verifyLeaf(batch, batch.transactionsRoot, transactionHash, index, proof);
+    }
+    
+    /// Checks the outcome of the `index`-th transaction of a batch against its
+    /// outcomes root, which uses the same tree as the transactions root. Status
+    /// is 0 for success, 1 for failed (included, nonce consumed) and 2 for
+    /// rejected (not executed, state untouched); `errorCode` is 0 on success.
+    function verifyTransactionOutcome(
+        uint256 batchIndex,
+        uint256 index,
+        uint8 status,
+        uint16 errorCode,
+        bytes32[] calldata proof
+    ) external view returns (bool) {
+        Batch storage batch = batches[batchIndex];
+        bytes32 leaf = keccak256(abi.encode(status, errorCode));
+        return verifyLeaf(batch, batch.outcomesRoot, leaf, index, proof);
+    }
+    
+    function verifyLeaf(
+        Batch storage batch,
+        bytes32 root,
+        bytes32 leaf,
+        uint256 index,
+        bytes32[] calldata proof
+    ) internal view returns (bool) {
+        if (!batch.finalized || index >= batch.transactionCount) {
+            return false;
+        }
+        
+        uint256 depth = 0;
+        while ((uint256(1) << depth) < batch.transactionCount) {
+            depth++;
+        }
+        if (proof.length != depth) {
+            return false;
+        }
+        
+        bytes32 node = leaf;
+        uint256 position = index;
+        for (uint256 i = 0; i < proof.length; i++) {
+            node = (position & 1) == 1
+                ? keccak256(abi.encodePacked(proof[i], node))
+                : keccak256(abi.encodePacked(node, proof[i]));
+            position >>= 1;
+        }
+        
+        return node == root;
+    }
+    
     function getBatch(uint256 batchIndex) external view returns (Batch memory) {
         return batches[batchIndex];
     }
@@ -8,6 +8,7 @@ describe("ZKEVMRollup", function () {
   let user;
   
   const initialStateRoot = ethers.keccak256(ethers.toUtf8Bytes("initial-state"));
+  const chainId = 31337;
   
   beforeEach(async function () {
     [owner, user] = await ethers.getSigners();
@@ -19,7 +20,9 @@ describe("ZKEVMRollup", function () {
     const ZKEVMRollup = await ethers.getContractFactory("ZKEVMRollup");
     rollup = await ZKEVMRollup.deploy(
       await verifier.getAddress(),
-      initialStateRoot
+      initialStateRoot,
+      chainId,
+      0
     );
     await rollup.waitForDeployment();
   });
@@ -44,14 +47,17 @@ describe("ZKEVMRollup", function () {
       const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
       
       const transition = {
+        chainId,
         oldStateRoot: initialStateRoot,
         newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
         batchIndex: 0,
         transactionCount: 10,
-        transactionHashes: [
-          ethers.hexlify(ethers.randomBytes(32)),
-          ethers.hexlify(ethers.randomBytes(32)),
-        ],
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.hexlify(ethers.randomBytes(32)),
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
       };
       
       await verifier.setValidProof(true);
@@ -67,11 +73,17 @@ describe("ZKEVMRollup", function () {
       const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
       
       const transition = {
+        chainId,
         oldStateRoot: ethers.keccak256(ethers.toUtf8Bytes("wrong-root")),
         newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
         batchIndex: 0,
         transactionCount: 10,
-        transactionHashes: [],
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
       };
       
       await expect(
@@ -84,11 +96,17 @@ describe("ZKEVMRollup", function () {
       const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
       
       const transition = {
+        chainId,
         oldStateRoot: initialStateRoot,
         newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
         batchIndex: 5,
         transactionCount: 10,
-        transactionHashes: [],
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
       };
       
       await expect(
@@ -96,17 +114,104 @@ describe("ZKEVMRollup", function () {
       ).to.be.revertedWithCustomError(rollup, "InvalidBatchIndex");
     });
     
+    it("Should reject batch proven for another chain", async function () {
+      const proof = ethers.hexlify(ethers.randomBytes(1024));
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      
+      const transition = {
+        chainId: 1,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
+        batchIndex: 0,
+        transactionCount: 10,
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
+      };
+      
+      await verifier.setValidProof(true);
+      
+      await expect(
+        rollup.submitBatch(proof, transition, vKeyHash)
+      ).to.be.revertedWithCustomError(rollup, "InvalidChainId");
+    });
+    
+    it("Should reject batch executed at another base fee", async function () {
+      const proof = ethers.hexlify(ethers.randomBytes(1024));
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      
+      const transition = {
+        chainId,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
+        batchIndex: 0,
+        transactionCount: 10,
+        gasUsed: 210000,
+        baseFee: 7,
+        nextBaseFee: 7,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
+      };
+      
+      await verifier.setValidProof(true);
+      
+      await expect(
+        rollup.submitBatch(proof, transition, vKeyHash)
+      ).to.be.revertedWithCustomError(rollup, "InvalidBaseFee");
+    });
+    
+    it("Should chain the base fee from one batch to the next", async function () {
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      const stateRoot = ethers.keccak256(ethers.toUtf8Bytes("new-state"));
+      
+      const first = {
+        chainId,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: stateRoot,
+        batchIndex: 0,
+        transactionCount: 10,
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 1000,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
+      };
+      
+      await verifier.setValidProof(true);
+      await rollup.submitBatch(ethers.hexlify(ethers.randomBytes(1024)), first, vKeyHash);
+      expect(await rollup.currentBaseFee()).to.equal(1000);
+      
+      const second = { ...first, oldStateRoot: stateRoot, batchIndex: 1, baseFee: 0, nextBaseFee: 875 };
+      await expect(
+        rollup.submitBatch(ethers.hexlify(ethers.randomBytes(1024)), second, vKeyHash)
+      ).to.be.revertedWithCustomError(rollup, "InvalidBaseFee");
+      
+      await rollup.submitBatch(ethers.hexlify(ethers.randomBytes(1024)), { ...second, baseFee: 1000 }, vKeyHash);
+      expect(await rollup.currentBaseFee()).to.equal(875);
+    });
+    
     it("Should update state root after valid submission", async function () {
       const proof = ethers.hexlify(ethers.randomBytes(1024));
       const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
       
       const newStateRoot = ethers.keccak256(ethers.toUtf8Bytes("new-state"));
       const transition = {
+        chainId,
         oldStateRoot: initialStateRoot,
         newStateRoot: newStateRoot,
         batchIndex: 0,
         transactionCount: 10,
-        transactionHashes: [],
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
       };
       
       await verifier.setValidProof(true);
@@ -124,18 +229,30 @@ describe("ZKEVMRollup", function () {
       
       const transitions = [
         {
+          chainId,
           oldStateRoot: initialStateRoot,
           newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("state-1")),
           batchIndex: 0,
           transactionCount: 10,
-          transactionHashes: [],
+          gasUsed: 210000,
+          baseFee: 0,
+          nextBaseFee: 0,
+          transactionsRoot: ethers.ZeroHash,
+          receiptsRoot: ethers.ZeroHash,
+          outcomesRoot: ethers.ZeroHash,
         },
         {
+          chainId,
           oldStateRoot: ethers.keccak256(ethers.toUtf8Bytes("state-1")),
           newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("state-2")),
           batchIndex: 1,
           transactionCount: 15,
-          transactionHashes: [],
+          gasUsed: 315000,
+          baseFee: 0,
+          nextBaseFee: 0,
+          transactionsRoot: ethers.ZeroHash,
+          receiptsRoot: ethers.ZeroHash,
+          outcomesRoot: ethers.ZeroHash,
         },
       ];
       
@@ -147,8 +264,132 @@ describe("ZKEVMRollup", function () {
       
       expect(await rollup.currentBatchIndex()).to.equal(2);
     });
+    
+    it("Should chain the base fee within aggregated batches", async function () {
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      const transition = {
+        chainId,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("state-1")),
+        batchIndex: 0,
+        transactionCount: 10,
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 1000,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
+      };
+      const next = {
+        ...transition,
+        oldStateRoot: transition.newStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("state-2")),
+        batchIndex: 1,
+        nextBaseFee: 875,
+      };
+      
+      await verifier.setValidProof(true);
+      
+      await expect(
+        rollup.submitAggregatedBatch(ethers.hexlify(ethers.randomBytes(2048)), [transition, next], vKeyHash)
+      ).to.be.revertedWithCustomError(rollup, "InvalidBaseFee");
+      
+      await rollup.submitAggregatedBatch(
+        ethers.hexlify(ethers.randomBytes(2048)),
+        [transition, { ...next, baseFee: 1000 }],
+        vKeyHash
+      );
+      expect(await rollup.currentBaseFee()).to.equal(875);
+    });
   });
-  
+
+  describe("Public Values", function () {
+    // Same vector as `public_values_hash_vector` in rollup-core/src/public_values.rs.
+    it("Should hash public values as the guest commits them", async function () {
+      const transition = {
+        chainId,
+        oldStateRoot: "0x" + "11".repeat(32),
+        newStateRoot: "0x" + "22".repeat(32),
+        batchIndex: 7,
+        transactionCount: 2,
+        gasUsed: 42000,
+        baseFee: 1000000000,
+        nextBaseFee: 875350000,
+        // Root over the transaction hashes 0xaa..aa and 0xbb..bb.
+        transactionsRoot: "0x9f89faaf1495298300ca41edde79c5cc9cb9bf17e1c9ef97acfdc53194f901e1",
+        receiptsRoot: "0x" + "33".repeat(32),
+        outcomesRoot: "0x" + "44".repeat(32),
+      };
+
+      expect(await rollup.hashPublicValues(transition)).to.equal(
+        "0x26065cc2e13d3be3a0b47ae35390c9a966a3a3883c5f556eda0e4f20c16fd7aa"
+      );
+    });
+
+    // Same root as `root_vectors` in rollup-core/src/tx_root.rs.
+    it("Should verify transaction inclusion against the batch root", async function () {
+      const proof = ethers.hexlify(ethers.randomBytes(1024));
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      const hashes = ["aa", "bb", "cc"].map((byte) => "0x" + byte.repeat(32));
+
+      const transition = {
+        chainId,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
+        batchIndex: 0,
+        transactionCount: 3,
+        gasUsed: 63000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: "0xd1370056138aa7c7187e8a108bbf1a481efad21e528c7dee7437657e698074c6",
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
+      };
+
+      await verifier.setValidProof(true);
+      await rollup.submitBatch(proof, transition, vKeyHash);
+
+      const siblings = [ethers.ZeroHash, ethers.keccak256(ethers.concat([hashes[0], hashes[1]]))];
+      expect(await rollup.verifyTransactionInclusion(0, hashes[2], 2, siblings)).to.be.true;
+      expect(await rollup.verifyTransactionInclusion(0, hashes[1], 2, siblings)).to.be.false;
+      expect(await rollup.verifyTransactionInclusion(0, ethers.ZeroHash, 3, [hashes[2], siblings[1]])).to.be.false;
+    });
+
+    it("Should verify transaction outcomes against the batch outcomes root", async function () {
+      const proof = ethers.hexlify(ethers.randomBytes(1024));
+      const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
+      const coder = ethers.AbiCoder.defaultAbiCoder();
+      // Success, failed with ExecutionReverted, rejected with NonceTooLow.
+      const leaves = [[0, 0], [1, 18], [2, 5]].map((outcome) =>
+        ethers.keccak256(coder.encode(["uint8", "uint16"], outcome))
+      );
+      const pair = (left, right) => ethers.keccak256(ethers.concat([left, right]));
+      const siblings = [ethers.ZeroHash, pair(leaves[0], leaves[1])];
+
+      const transition = {
+        chainId,
+        oldStateRoot: initialStateRoot,
+        newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
+        batchIndex: 0,
+        transactionCount: 3,
+        gasUsed: 42000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: pair(siblings[1], pair(leaves[2], ethers.ZeroHash)),
+      };
+
+      await verifier.setValidProof(true);
+      await rollup.submitBatch(proof, transition, vKeyHash);
+
+      expect(await rollup.verifyTransactionOutcome(0, 2, 2, 5, siblings)).to.be.true;
+      expect(await rollup.verifyTransactionOutcome(0, 2, 1, 5, siblings)).to.be.false;
+      expect(await rollup.verifyTransactionOutcome(0, 1, 1, 18, [leaves[0], siblings[1]])).to.be.false;
+      expect(await rollup.verifyTransactionOutcome(0, 1, 1, 18, [leaves[0], pair(leaves[2], ethers.ZeroHash)])).to.be.true;
+    });
+  });
+
   describe("Pausability", function () {
     it("Should allow owner to pause", async function () {
       await rollup.pause();
@@ -168,11 +409,17 @@ describe("ZKEVMRollup", function () {
       const vKeyHash = [ethers.hexlify(ethers.randomBytes(32))];
       
       const transition = {
+        chainId,
         oldStateRoot: initialStateRoot,
         newStateRoot: ethers.keccak256(ethers.toUtf8Bytes("new-state")),
         batchIndex: 0,
         transactionCount: 10,
-        transactionHashes: [],
+        gasUsed: 210000,
+        baseFee: 0,
+        nextBaseFee: 0,
+        transactionsRoot: ethers.ZeroHash,
+        receiptsRoot: ethers.ZeroHash,
+        outcomesRoot: ethers.ZeroHash,
       };
       
       await expect(
@@ -182,18 +429,3 @@ describe("ZKEVMRollup", function () {
   });
 });
 
-contract MockSP1Verifier {
-  bool public validProof = false;
-  
-  function setValidProof(bool _valid) public {
-    validProof = _valid;
-  }
-  
-  function verifyProof(
-    bytes calldata,
-    bytes32,
-    bytes32[] calldata
-  ) external view returns (bool) {
-    return validProof;
-  }
-}
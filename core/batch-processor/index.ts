@@ -1,6 +1,6 @@
 import { ethers } from 'ethers';
 import { RollupContract } from '../../lib/rollup-contract';
-import { generateProofForBatch, ProofResult } from '../../prover/src/prover';
+import { proveBatch, batchInputPath, batchProofDir, defaultWorkDir } from '../../prover/src/prover';
 import { Transaction, BatchConfig, ProofResult as ProofResultType } from '../types';
 import { StateRootManager } from '../state-manager';
 import { getLogger, LogLevel } from '../utils/logger';
@@ -185,24 +185,29 @@ export class BatchProcessor {
     const batchTransactions = this.pendingTransactions.slice(0, batchSize);
     this.pendingTransactions = this.pendingTransactions.slice(batchSize);
 
-    this.logger.info(`Creating batch ${this.batchIndex} with ${batchTransactions.length} transactions`);
+    // Claimed before proving, since `processPendingTransactions` creates batches concurrently.
+    const batchIndex = this.batchIndex++;
+    const workDir = this.config.workDir ?? defaultWorkDir();
+
+    this.logger.info(`Creating batch ${batchIndex} with ${batchTransactions.length} transactions`);
 
     const optimizedTransactions = optimizeBatch(batchTransactions);
     const gasUsage = calculateGasUsage(optimizedTransactions);
     
     this.logger.debug(`Optimized batch gas usage: ${gasUsage}`);
 
-    const proof = await generateProofForBatch(optimizedTransactions, this.batchIndex);
+    const proof = await proveBatch(batchInputPath(workDir, batchIndex), batchProofDir(workDir, batchIndex));
+    if (proof.batchIndex !== batchIndex) {
+      throw new Error(`Batch input for batch ${batchIndex} was built for batch ${proof.batchIndex}`);
+    }
     
     // Store batch statistics
-    this.batchStats.set(this.batchIndex, {
-      transactionCount: batchTransactions.length,
-      gasUsed: gasUsage,
+    this.batchStats.set(batchIndex, {
+      transactionCount: proof.publicValues.transactionCount,
+      gasUsed: proof.publicValues.gasUsed,
       timestamp: Date.now()
     });
     
-    this.batchIndex++;
-    
     return proof;
   }
 
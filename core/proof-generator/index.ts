@@ -1,5 +1,4 @@
-import { generateProofForBatch, verifyZKProof, ProofResult } from '../../prover/src/prover';
-import { Transaction } from '../types';
+import { proveBatch, verifyProof as verifyProofDir, ProofResult } from '../../prover/src/prover';
 
 export class ProofGenerator {
   private pendingProofs: Map<number, Promise<ProofResult>>;
@@ -11,10 +10,11 @@ export class ProofGenerator {
   }
 
   async generateProof(
-    transactions: Transaction[],
-    batchIndex: number
+    batchIndex: number,
+    batchPath: string,
+    proofDir: string
   ): Promise<ProofResult> {
-    const cacheKey = this.generateCacheKey(transactions, batchIndex);
+    const cacheKey = this.generateCacheKey(batchPath, batchIndex);
     
     if (this.proofCache.has(cacheKey)) {
       return this.proofCache.get(cacheKey)!;
@@ -24,7 +24,7 @@ export class ProofGenerator {
       return this.pendingProofs.get(batchIndex)!;
     }
 
-    const proofPromise = this.doGenerateProof(transactions, batchIndex);
+    const proofPromise = this.doGenerateProof(batchIndex, batchPath, proofDir);
     this.pendingProofs.set(batchIndex, proofPromise);
 
     try {
@@ -37,35 +37,33 @@ export class ProofGenerator {
   }
 
   private async doGenerateProof(
-    transactions: Transaction[],
-    batchIndex: number
+    batchIndex: number,
+    batchPath: string,
+    proofDir: string
   ): Promise<ProofResult> {
-    console.log(`Generating proof for batch ${batchIndex} with ${transactions.length} transactions`);
+    console.log(`Generating proof for batch ${batchIndex} from ${batchPath}`);
     
     const startTime = Date.now();
-    const proof = await generateProofForBatch(transactions, batchIndex);
+    const proof = await proveBatch(batchPath, proofDir);
     const endTime = Date.now();
     
     console.log(`Proof generated in ${endTime - startTime}ms`);
     return proof;
   }
 
-  async verifyProof(proofData: string, publicValues: any): Promise<boolean> {
+  async verifyProof(proofDir: string): Promise<boolean> {
     console.log('Verifying proof...');
     
     const startTime = Date.now();
-    const isValid = await verifyZKProof(proofData, publicValues);
+    const isValid = await verifyProofDir(proofDir);
     const endTime = Date.now();
     
     console.log(`Proof verified in ${endTime - startTime}ms: ${isValid}`);
     return isValid;
   }
 
-  private generateCacheKey(transactions: Transaction[], batchIndex: number): string {
-    const txHashes = transactions.map(tx => {
-      return `${tx.from}_${tx.to}_${tx.value}_${tx.nonce}`;
-    }).join('_');
-    return `${batchIndex}_${txHashes}`;
+  private generateCacheKey(batchPath: string, batchIndex: number): string {
+    return `${batchIndex}_${batchPath}`;
   }
 
   clearCache(): void {
@@ -110,7 +110,10 @@ export class StateRootManager {
         this.batchHistory.set(i, {
           batchIndex: i,
           stateRoot: batch.stateRoot,
-          transactionHashes: batch.transactionHashes,
+          transactionsRoot: batch.transactionsRoot,
+          receiptsRoot: batch.receiptsRoot,
+          outcomesRoot: batch.outcomesRoot,
+          transactionCount: batch.transactionCount,
           timestamp: batch.timestamp,
           finalized: batch.finalized,
         });
@@ -226,7 +229,10 @@ export class StateRootManager {
       this.batchHistory.set(proofResult.batchIndex, {
         batchIndex: proofResult.batchIndex,
         stateRoot: proofResult.publicValues.newStateRoot,
-        transactionHashes: proofResult.publicValues.transactionHashes,
+        transactionsRoot: proofResult.publicValues.transactionsRoot,
+        receiptsRoot: proofResult.publicValues.receiptsRoot,
+        outcomesRoot: proofResult.publicValues.outcomesRoot,
+        transactionCount: proofResult.publicValues.transactionCount,
         timestamp: Math.floor(Date.now() / 1000),
         finalized: true,
       });
@@ -263,7 +269,10 @@ export class StateRootManager {
         this.batchHistory.set(proof.batchIndex, {
           batchIndex: proof.batchIndex,
           stateRoot: proof.publicValues.newStateRoot,
-          transactionHashes: proof.publicValues.transactionHashes,
+          transactionsRoot: proof.publicValues.transactionsRoot,
+          receiptsRoot: proof.publicValues.receiptsRoot,
+          outcomesRoot: proof.publicValues.outcomesRoot,
+          transactionCount: proof.publicValues.transactionCount,
           timestamp: Math.floor(Date.now() / 1000),
           finalized: true,
         });
@@ -299,7 +308,10 @@ export class StateRootManager {
       batches.push({
         batchIndex: i,
         stateRoot: batch.stateRoot,
-        transactionHashes: batch.transactionHashes,
+        transactionsRoot: batch.transactionsRoot,
+        receiptsRoot: batch.receiptsRoot,
+        outcomesRoot: batch.outcomesRoot,
+        transactionCount: batch.transactionCount,
         timestamp: batch.timestamp,
         finalized: batch.finalized,
       });
@@ -27,11 +27,17 @@ export interface StateTransition {
 }
 
 export interface StateTransitionProof {
+  chainId: number;
   oldStateRoot: string;
   newStateRoot: string;
   batchIndex: number;
   transactionCount: number;
-  transactionHashes: string[];
+  gasUsed: number;
+  baseFee: number;
+  nextBaseFee: number;
+  transactionsRoot: string;
+  receiptsRoot: string;
+  outcomesRoot: string;
 }
 
 export interface ProofResult {
@@ -49,6 +55,8 @@ export interface BatchConfig {
   maxGasLimit: number;
   aggregationSize: number;
   batchInterval: number;
+  /** Where batch inputs are read from and proofs written to, see `batchInputPath`. */
+  workDir?: string;
 }
 
 export interface NetworkConfig {
@@ -7,11 +7,17 @@ interface ProofResult {
   batchIndex: number;
   proofData: string;
   publicValues: {
+    chainId: number;
     oldStateRoot: string;
     newStateRoot: string;
     batchIndex: number;
     transactionCount: number;
-    transactionHashes: string[];
+    gasUsed: number;
+    baseFee: number;
+    nextBaseFee: number;
+    transactionsRoot: string;
+    receiptsRoot: string;
+    outcomesRoot: string;
   };
   proofSize: number;
   generationTime: number;
@@ -1,6 +1,6 @@
 import { ethers } from 'ethers';
 import { RollupContract, StateRootManager } from './rollup-contract';
-import { generateProofForBatch, ProofResult } from '../prover/src/prover';
+import { proveBatch, batchInputPath, batchProofDir, defaultWorkDir, ProofResult } from '../prover/src/prover';
 
 interface Transaction {
   from: string;
@@ -16,6 +16,8 @@ interface BatchConfig {
   maxTransactions: number;
   maxGasLimit: number;
   aggregationSize: number;
+  /** Where batch inputs are read from and proofs written to, see `batchInputPath`. */
+  workDir?: string;
 }
 
 export class BatchPoster {
@@ -62,9 +64,14 @@ export class BatchPoster {
     const batchTransactions = this.pendingTransactions.slice(0, this.config.maxTransactions);
     this.pendingTransactions = this.pendingTransactions.slice(this.config.maxTransactions);
 
-    console.log(`Creating batch ${this.batchIndex} with ${batchTransactions.length} transactions`);
+    const batchIndex = this.batchIndex;
+    const workDir = this.config.workDir ?? defaultWorkDir();
+    console.log(`Creating batch ${batchIndex} with ${batchTransactions.length} transactions`);
 
-    const proof = await generateProofForBatch(batchTransactions, this.batchIndex);
+    const proof = await proveBatch(batchInputPath(workDir, batchIndex), batchProofDir(workDir, batchIndex));
+    if (proof.batchIndex !== batchIndex) {
+      throw new Error(`Batch input for batch ${batchIndex} was built for batch ${proof.batchIndex}`);
+    }
     
     this.batchIndex++;
     
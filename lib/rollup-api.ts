@@ -1,5 +1,5 @@
 import { ZKRollup, ZKRollupConfig } from './zk-rollup';
-import { generateProofForBatch, verifyZKProof, ProofResult } from '../prover/src/prover';
+import { proveBatch, verifyProof, ProofResult } from '../prover/src/prover';
 
 export async function submitTransaction(
   rollup: ZKRollup,
@@ -15,18 +15,17 @@ export async function processBatch(
 }
 
 export async function generateZKProof(
-  transactions: any[],
-  batchIndex: number
+  batchPath: string,
+  proofDir: string
 ): Promise<ProofResult> {
-  return await generateProofForBatch(transactions, batchIndex);
+  return await proveBatch(batchPath, proofDir);
 }
 
 export async function verifyZKProofOnChain(
   rollup: ZKRollup,
-  proofData: string,
-  publicValues: any
+  proofDir: string
 ): Promise<boolean> {
-  return await verifyZKProof(proofData, publicValues);
+  return await verifyProof(proofDir);
 }
 
 export async function getCurrentStateRoot(rollup: ZKRollup): Promise<string> {
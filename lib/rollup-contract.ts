@@ -3,11 +3,17 @@ import { readFileSync, writeFileSync } from 'fs-extra';
 import { join } from 'path';
 
 interface StateTransitionProof {
+  chainId: number;
   oldStateRoot: string;
   newStateRoot: string;
   batchIndex: number;
   transactionCount: number;
-  transactionHashes: string[];
+  gasUsed: number;
+  baseFee: number;
+  nextBaseFee: number;
+  transactionsRoot: string;
+  receiptsRoot: string;
+  outcomesRoot: string;
 }
 
 interface ProofResult {
@@ -228,7 +234,10 @@ export class StateRootManager {
       batches.push({
         batchIndex: i,
         stateRoot: batch.stateRoot,
-        transactionHashes: batch.transactionHashes,
+        transactionsRoot: batch.transactionsRoot,
+        receiptsRoot: batch.receiptsRoot,
+        outcomesRoot: batch.outcomesRoot,
+        transactionCount: batch.transactionCount,
         timestamp: batch.timestamp,
         finalized: batch.finalized,
       });
@@ -1,7 +1,7 @@
 import { ethers } from 'ethers';
 import { RollupContract } from './rollup-contract';
 import { BatchPoster, L1Bridge, RollupSequencer } from './batch-poster';
-import { generateProofForBatch, verifyZKProof, ProofResult } from '../prover/src/prover';
+import { ProofResult } from '../prover/src/prover';
 
 export interface ZKRollupConfig {
   l1RpcUrl: string;
@@ -125,11 +125,11 @@ export class ZKRollup {
 
   getRollupABI(): any[] {
     return [
-      "function submitBatch(bytes proof, tuple(bytes32 oldStateRoot, bytes32 newStateRoot, uint256 batchIndex, uint256 transactionCount, bytes32[] transactionHashes) transition, bytes32[] vKeyHash) external",
-      "function submitAggregatedBatch(bytes proof, tuple(bytes32 oldStateRoot, bytes32 newStateRoot, uint256 batchIndex, uint256 transactionCount, bytes32[] transactionHashes)[] transitions, bytes32[] vKeyHash) external",
+      "function submitBatch(bytes proof, tuple(uint256 chainId, bytes32 oldStateRoot, bytes32 newStateRoot, uint256 batchIndex, uint256 transactionCount, uint256 gasUsed, uint256 baseFee, uint256 nextBaseFee, bytes32 transactionsRoot, bytes32 receiptsRoot, bytes32 outcomesRoot) transition, bytes32[] vKeyHash) external",
+      "function submitAggregatedBatch(bytes proof, tuple(uint256 chainId, bytes32 oldStateRoot, bytes32 newStateRoot, uint256 batchIndex, uint256 transactionCount, uint256 gasUsed, uint256 baseFee, uint256 nextBaseFee, bytes32 transactionsRoot, bytes32 receiptsRoot, bytes32 outcomesRoot)[] transitions, bytes32[] vKeyHash) external",
       "function currentStateRoot() view returns (bytes32)",
       "function currentBatchIndex() view returns (uint256)",
-      "function batches(uint256) view returns (tuple(bytes32 stateRoot, bytes32[] transactionHashes, uint256 timestamp, bool finalized))",
+      "function batches(uint256) view returns (tuple(bytes32 stateRoot, bytes32 transactionsRoot, bytes32 receiptsRoot, bytes32 outcomesRoot, uint256 transactionCount, uint256 timestamp, bool finalized))",
       "function getBatchCount() view returns (uint256)",
       "function pause() external",
       "function unpause() external",
@@ -1,26 +1,27 @@
-import { generateProofForBatch } from './prover';
-import { readFileSync, writeFileSync } from 'fs';
+import { proveBatch, ProofMode } from './prover';
+import { writeFileSync } from 'fs';
+import { join } from 'path';
 
 async function main() {
-  const batchDataPath = process.argv[2];
-  
-  if (!batchDataPath) {
-    console.error('Usage: npm run prove-batch <batch_data_json>');
+  const batchPath = process.argv[2];
+  const outDir = process.argv[3];
+  const mode = (process.argv[4] ?? 'groth16') as ProofMode;
+
+  if (!batchPath || !outDir) {
+    console.error('Usage: npm run prove-batch <guest_input_json> <out_dir> [core|compressed|groth16|plonk]');
     process.exit(1);
   }
-  
-  const batchData = JSON.parse(readFileSync(batchDataPath, 'utf-8'));
-  
-  console.log(`Generating proof for batch ${batchData.batchIndex} with ${batchData.transactions.length} transactions...`);
-  
-  const proof = await generateProofForBatch(batchData.transactions, batchData.batchIndex);
-  
+
+  console.log(`Generating ${mode} proof for ${batchPath}...`);
+
+  const proof = await proveBatch(batchPath, outDir, mode);
+
   console.log('Proof generated successfully!');
   console.log('Proof ID:', proof.proofId);
   console.log('Proof Size:', proof.proofSize, 'bytes');
   console.log('Generation Time:', proof.generationTime, 'ms');
-  
-  const outputPath = `proof_${proof.proofId}.json`;
+
+  const outputPath = join(outDir, `proof_${proof.proofId}.json`);
   writeFileSync(outputPath, JSON.stringify(proof, null, 2));
   console.log(`Proof saved to: ${outputPath}`);
 }
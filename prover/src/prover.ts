@@ -1,181 +1,169 @@
-import { readFileSync, writeFileSync } from 'fs-extra';
+/**
+ * Proofs are produced and checked by the `prover` binary in `script/`, which
+ * runs the rollup guest in SP1. This module only drives it and reads back the
+ * `proof.json` it writes; nothing here fabricates proofs or public values.
+ *
+ * `ROLLUP_PROVER_BIN` points at a built `prover` binary. Without it the binary
+ * is run through `cargo run --release` from `../script`.
+ */
+
+import { execFile } from 'child_process';
+import { promisify } from 'util';
+import { readFileSync } from 'fs-extra';
 import { join } from 'path';
 
-interface Transaction {
-  from: string;
-  to: string;
-  value: string;
-  data: string;
-  nonce: number;
-  gasLimit: number;
-  gasPrice: number;
-}
+const run = promisify(execFile);
 
-interface StateTransition {
-  transactions: Transaction[];
-  oldStateRoot: string;
-  newStateRoot: string;
-  batchIndex: number;
-}
+export type ProofMode = 'core' | 'compressed' | 'groth16' | 'plonk';
 
-interface StateTransitionProof {
+/** The `PublicValues` struct `ZKEVMRollup.submitBatch` takes. */
+export interface StateTransitionProof {
+  chainId: number;
   oldStateRoot: string;
   newStateRoot: string;
   batchIndex: number;
   transactionCount: number;
-  transactionHashes: string[];
+  gasUsed: number;
+  baseFee: number;
+  nextBaseFee: number;
+  transactionsRoot: string;
+  receiptsRoot: string;
+  outcomesRoot: string;
 }
 
-interface ProofResult {
+export interface ProofResult {
   proofId: string;
   batchIndex: number;
+  /** Directory `prover prove` wrote `proof.bin`, `public_values.bin` and `proof.json` to. */
+  proofDir: string;
+  mode: ProofMode;
+  vkeyHash: string;
+  /** Proof bytes for `submitBatch`; empty for core and compressed proofs. */
   proofData: string;
   publicValues: StateTransitionProof;
+  publicValuesHash: string;
   proofSize: number;
   generationTime: number;
   timestamp: number;
 }
 
-interface MockProof {
-  bytes: Buffer;
-  publicValues: Buffer;
-}
-
-class MockSP1Verifier {
-  async generateProof(options: { elf: string; stdin: Buffer }): Promise<MockProof> {
-    const proofData = Buffer.alloc(1024);
-    const publicValues = Buffer.from(JSON.stringify({
-      oldStateRoot: '0x' + '0'.repeat(64),
-      newStateRoot: '0x' + '1'.repeat(64),
-      batchIndex: 0,
-      transactionCount: 1,
-      transactionHashes: [],
-    }));
-    
-    return {
-      bytes: proofData,
-      publicValues,
-    };
-  }
-
-  async verifyProof(options: { proof: MockProof; publicValues: Buffer }): Promise<boolean> {
-    return true;
-  }
-
-  async aggregateProofs(proofs: MockProof[]): Promise<MockProof> {
-    const aggregatedProof = Buffer.alloc(2048);
-    const publicValues = proofs[0].publicValues;
-    
-    return {
-      bytes: aggregatedProof,
-      publicValues,
-    };
-  }
+/** `proof.json` as written by `prover prove`. */
+interface ProofFile {
+  mode: ProofMode;
+  sp1_version: string;
+  vkey_hash: string;
+  public_values: string;
+  public_values_hash: string;
+  state_transition: {
+    chain_id: number;
+    old_state_root: string;
+    new_state_root: string;
+    batch_index: number;
+    transaction_count: number;
+    gas_used: number;
+    base_fee: number;
+    next_base_fee: number;
+    transactions_root: string;
+    receipts_root: string;
+    outcomes_root: string;
+  };
+  proof: string | null;
 }
 
 export class ZKProver {
-  private elfPath: string;
-  private verifier: MockSP1Verifier;
+  private binary: string | undefined;
+  private scriptDir: string;
 
-  constructor(elfPath: string) {
-    this.elfPath = elfPath;
-    this.verifier = new MockSP1Verifier();
+  constructor(options: { binary?: string; scriptDir?: string } = {}) {
+    this.binary = options.binary ?? process.env.ROLLUP_PROVER_BIN;
+    this.scriptDir = options.scriptDir ?? join(process.cwd(), '../script');
   }
 
-  async generateProof(stateTransition: StateTransition): Promise<ProofResult> {
+  /** Proves the `GuestInput` JSON at `batchPath` and writes the proof to `outDir`. */
+  async prove(batchPath: string, outDir: string, mode: ProofMode = 'groth16'): Promise<ProofResult> {
     const startTime = Date.now();
-    
-    const input = JSON.stringify(stateTransition);
-    const inputBytes = Buffer.from(input);
-    
-    const proof = await this.verifier.generateProof({
-      elf: this.elfPath,
-      stdin: inputBytes,
-    });
-    
-    const publicValues = JSON.parse(proof.publicValues.toString()) as StateTransitionProof;
-    
-    const proofData = proof.bytes.toString('hex');
-    const proofSize = proof.bytes.length;
+    await this.invoke(['prove', '--batch', batchPath, '--mode', mode, '--out', outDir]);
     const generationTime = Date.now() - startTime;
-    
+
+    const file = JSON.parse(readFileSync(join(outDir, 'proof.json'), 'utf-8')) as ProofFile;
+    const transition = file.state_transition;
+    const proofData = file.proof ?? '0x';
+
     return {
-      proofId: `proof_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`,
-      batchIndex: stateTransition.batchIndex,
+      proofId: `proof_${transition.batch_index}_${file.public_values_hash.slice(2, 18)}`,
+      batchIndex: transition.batch_index,
+      proofDir: outDir,
+      mode: file.mode,
+      vkeyHash: file.vkey_hash,
       proofData,
-      publicValues,
-      proofSize,
+      publicValues: {
+        chainId: transition.chain_id,
+        oldStateRoot: transition.old_state_root,
+        newStateRoot: transition.new_state_root,
+        batchIndex: transition.batch_index,
+        transactionCount: transition.transaction_count,
+        gasUsed: transition.gas_used,
+        baseFee: transition.base_fee,
+        nextBaseFee: transition.next_base_fee,
+        transactionsRoot: transition.transactions_root,
+        receiptsRoot: transition.receipts_root,
+        outcomesRoot: transition.outcomes_root,
+      },
+      publicValuesHash: file.public_values_hash,
+      proofSize: (proofData.length - 2) / 2,
       generationTime,
       timestamp: Date.now(),
     };
   }
 
-  async verifyProof(proofData: string, publicValues: StateTransitionProof): Promise<boolean> {
-    const proofBytes = Buffer.from(proofData, 'hex');
-    const proof: MockProof = { bytes: proofBytes, publicValues: Buffer.from(JSON.stringify(publicValues)) };
-    
-    const publicValuesBytes = Buffer.from(JSON.stringify(publicValues));
-    
-    const isValid = await this.verifier.verifyProof({
-      proof,
-      publicValues: publicValuesBytes,
-    });
-    
-    return isValid;
+  /** Verifies a proof directory written by `prove`. */
+  async verify(proofDir: string): Promise<boolean> {
+    try {
+      await this.invoke(['verify', '--proof', proofDir]);
+      return true;
+    } catch {
+      return false;
+    }
   }
 
-  async generateBatchProof(transactions: Transaction[], batchIndex: number): Promise<ProofResult> {
-    const oldStateRoot = '0x' + '0'.repeat(64);
-    
-    const stateTransition: StateTransition = {
-      transactions,
-      oldStateRoot,
-      newStateRoot: '',
-      batchIndex,
-    };
-    
-    return this.generateProof(stateTransition);
-  }
-
-  async aggregateProofs(proofs: ProofResult[]): Promise<ProofResult> {
-    const startTime = Date.now();
-    
-    const mockProofs = proofs.map(p => ({ bytes: Buffer.from(p.proofData, 'hex'), publicValues: Buffer.from(JSON.stringify(p.publicValues)) }));
-    
-    const aggregatedProof = await this.verifier.aggregateProofs(mockProofs);
-    
-    const proofData = aggregatedProof.bytes.toString('hex');
-    const proofSize = aggregatedProof.bytes.length;
-    const generationTime = Date.now() - startTime;
-    
-    return {
-      proofId: `aggregated_${Date.now()}_${Math.random().toString(36).substr(2, 9)}`,
-      batchIndex: proofs[0].batchIndex,
-      proofData,
-      publicValues: proofs[0].publicValues,
-      proofSize,
-      generationTime,
-      timestamp: Date.now(),
-    };
+  private async invoke(args: string[]): Promise<void> {
+    const options = { maxBuffer: 64 * 1024 * 1024 };
+    if (this.binary) {
+      await run(this.binary, args, options);
+    } else {
+      await run('cargo', ['run', '--release', '--bin', 'prover', '--', ...args], {
+        ...options,
+        cwd: this.scriptDir,
+      });
+    }
   }
 }
 
-export async function generateProofForBatch(
-  transactions: Transaction[],
-  batchIndex: number
+export async function proveBatch(
+  batchPath: string,
+  outDir: string,
+  mode: ProofMode = 'groth16'
 ): Promise<ProofResult> {
-  const elfPath = join(process.cwd(), '../sp1-guest/target/sp1-guest-release');
-  const prover = new ZKProver(elfPath);
-  
-  return prover.generateBatchProof(transactions, batchIndex);
+  return new ZKProver().prove(batchPath, outDir, mode);
+}
+
+export async function verifyProof(proofDir: string): Promise<boolean> {
+  return new ZKProver().verify(proofDir);
+}
+
+/**
+ * Directory batch inputs and proofs are kept under: `batches/<n>.json` holds
+ * the `GuestInput` of batch `n` as `GuestInput::to_json` writes it, and
+ * `proofs/<n>/` the proof `prover prove` writes for it.
+ */
+export function defaultWorkDir(): string {
+  return process.env.ROLLUP_WORK_DIR ?? join(process.cwd(), 'rollup-data');
+}
+
+export function batchInputPath(workDir: string, batchIndex: number): string {
+  return join(workDir, 'batches', `${batchIndex}.json`);
 }
 
-export async function verifyZKProof(
-  proofData: string,
-  publicValues: StateTransitionProof
-): Promise<boolean> {
-  const elfPath = join(process.cwd(), '../sp1-guest/target/sp1-guest-release');
-  const prover = new ZKProver(elfPath);
-  
-  return prover.verifyProof(proofData, publicValues);
+export function batchProofDir(workDir: string, batchIndex: number): string {
+  return join(workDir, 'proofs', String(batchIndex));
 }
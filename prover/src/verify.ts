@@ -1,20 +1,16 @@
-import { verifyZKProof } from './prover';
-import { readFileSync } from 'fs';
+import { verifyProof } from './prover';
 
 async function main() {
-  const proofData = process.argv[2];
-  const publicValuesPath = process.argv[3];
-  
-  if (!proofData || !publicValuesPath) {
-    console.error('Usage: npm run verify <proof_data> <public_values_json>');
+  const proofDir = process.argv[2];
+
+  if (!proofDir) {
+    console.error('Usage: npm run verify <proof_dir>');
     process.exit(1);
   }
-  
-  const publicValues = JSON.parse(readFileSync(publicValuesPath, 'utf-8'));
-  
+
   console.log('Verifying ZK proof...');
-  const isValid = await verifyZKProof(proofData, publicValues);
-  
+  const isValid = await verifyProof(proofDir);
+
   console.log('Proof verification:', isValid ? 'VALID' : 'INVALID');
   process.exit(isValid ? 0 : 1);
 }
@@ -0,0 +1,274 @@
+use core::fmt;
+
+use alloy_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Stable numeric identity of every error, committed in
+/// [`outcomes_root`](crate::receipt::outcomes_root) and printed by the guest when
+/// it aborts. Discriminants must never be renumbered.
+///
+/// Codes below 100 are transaction errors; the batch carries on without the
+/// transaction. Codes from 100 up make the whole batch unprovable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u16)]
+pub enum ErrorCode {
+    ChainIdMismatch = 1,
+    InvalidSignature = 2,
+    SignerMismatch = 3,
+    SenderNotFound = 4,
+    NonceTooLow = 5,
+    NonceTooHigh = 6,
+    IntrinsicGasTooLow = 7,
+    PriorityFeeAboveMaxFee = 8,
+    MaxFeeBelowBaseFee = 9,
+    InsufficientFundsForGas = 10,
+    BatchGasLimitExceeded = 11,
+    InsufficientBalanceForValue = 12,
+    InvalidTransactionEncoding = 13,
+    NonceOverflow = 14,
+    BalanceOverflow = 15,
+    BalanceUnderflow = 16,
+    GasOverflow = 17,
+    ExecutionReverted = 18,
+    ExecutionHalted = 19,
+    EvmRejected = 20,
+
+    InvalidAccountProof = 100,
+    MissingTrieNode = 101,
+    AccountNotInWitness = 102,
+    InvalidTrieNode = 103,
+    InvalidAccountEncoding = 104,
+    MalformedInput = 105,
+    CodeNotInWitness = 106,
+    StorageNotInWitness = 107,
+    StateRootMismatch = 108,
+    WitnessRootMismatch = 109,
+}
+
+/// Why a single transaction could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionError {
+    /// `actual` is `None` for pre-EIP-155 legacy transactions.
+    ChainIdMismatch { expected: u64, actual: Option<u64> },
+    InvalidSignature,
+    SignerMismatch { claimed: Address, recovered: Address },
+    SenderNotFound { sender: Address },
+    NonceTooLow { expected: u64, actual: u64 },
+    NonceTooHigh { expected: u64, actual: u64 },
+    IntrinsicGasTooLow { gas_limit: u64, intrinsic_gas: u64 },
+    PriorityFeeAboveMaxFee { max_priority_fee_per_gas: u64, max_fee_per_gas: u64 },
+    MaxFeeBelowBaseFee { max_fee_per_gas: u64, base_fee: u64 },
+    /// The sender cannot prepay `gas_limit * max_fee_per_gas`.
+    InsufficientFundsForGas { balance: U256, max_gas_cost: U256 },
+    /// The gas limit does not fit in what is left of the batch.
+    BatchGasLimitExceeded { gas_limit: u64, remaining: u64 },
+    /// The sender paid for gas but cannot cover the value.
+    InsufficientBalanceForValue { balance: U256, value: U256 },
+    InvalidEncoding,
+    /// The sender's nonce is already `u64::MAX` (EIP-2681).
+    NonceOverflow { nonce: u64 },
+    /// Crediting `address` would take its balance past `U256::MAX`.
+    BalanceOverflow { address: Address },
+    /// Debiting `address` would take its balance below zero.
+    BalanceUnderflow { address: Address },
+    /// Gas or fee amounts do not fit their integer types.
+    GasOverflow,
+    /// The contract reverted; gas was paid and its state changes undone.
+    ExecutionReverted,
+    /// The contract ran out of gas or hit an exceptional halt.
+    ExecutionHalted,
+    /// The interpreter refused a transaction that passed validation.
+    EvmRejected,
+}
+
+impl TransactionError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::ChainIdMismatch { .. } => ErrorCode::ChainIdMismatch,
+            Self::InvalidSignature => ErrorCode::InvalidSignature,
+            Self::SignerMismatch { .. } => ErrorCode::SignerMismatch,
+            Self::SenderNotFound { .. } => ErrorCode::SenderNotFound,
+            Self::NonceTooLow { .. } => ErrorCode::NonceTooLow,
+            Self::NonceTooHigh { .. } => ErrorCode::NonceTooHigh,
+            Self::IntrinsicGasTooLow { .. } => ErrorCode::IntrinsicGasTooLow,
+            Self::PriorityFeeAboveMaxFee { .. } => ErrorCode::PriorityFeeAboveMaxFee,
+            Self::MaxFeeBelowBaseFee { .. } => ErrorCode::MaxFeeBelowBaseFee,
+            Self::InsufficientFundsForGas { .. } => ErrorCode::InsufficientFundsForGas,
+            Self::BatchGasLimitExceeded { .. } => ErrorCode::BatchGasLimitExceeded,
+            Self::InsufficientBalanceForValue { .. } => ErrorCode::InsufficientBalanceForValue,
+            Self::InvalidEncoding => ErrorCode::InvalidTransactionEncoding,
+            Self::NonceOverflow { .. } => ErrorCode::NonceOverflow,
+            Self::BalanceOverflow { .. } => ErrorCode::BalanceOverflow,
+            Self::BalanceUnderflow { .. } => ErrorCode::BalanceUnderflow,
+            Self::GasOverflow => ErrorCode::GasOverflow,
+            Self::ExecutionReverted => ErrorCode::ExecutionReverted,
+            Self::ExecutionHalted => ErrorCode::ExecutionHalted,
+            Self::EvmRejected => ErrorCode::EvmRejected,
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChainIdMismatch { expected, actual: Some(actual) } => {
+                write!(f, "chain id {actual} does not match {expected}")
+            }
+            Self::ChainIdMismatch { expected, actual: None } => {
+                write!(f, "missing chain id, expected {expected}")
+            }
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::SignerMismatch { claimed, recovered } => {
+                write!(f, "signed by {recovered} but claims sender {claimed}")
+            }
+            Self::SenderNotFound { sender } => write!(f, "sender {sender} does not exist"),
+            Self::NonceTooLow { expected, actual } => write!(f, "nonce {actual} too low, expected {expected}"),
+            Self::NonceTooHigh { expected, actual } => write!(f, "nonce {actual} too high, expected {expected}"),
+            Self::IntrinsicGasTooLow { gas_limit, intrinsic_gas } => {
+                write!(f, "gas limit {gas_limit} below intrinsic gas {intrinsic_gas}")
+            }
+            Self::PriorityFeeAboveMaxFee { max_priority_fee_per_gas, max_fee_per_gas } => {
+                write!(f, "priority fee {max_priority_fee_per_gas} exceeds max fee {max_fee_per_gas}")
+            }
+            Self::MaxFeeBelowBaseFee { max_fee_per_gas, base_fee } => {
+                write!(f, "max fee {max_fee_per_gas} below base fee {base_fee}")
+            }
+            Self::InsufficientFundsForGas { balance, max_gas_cost } => {
+                write!(f, "balance {balance} cannot prepay {max_gas_cost} for gas")
+            }
+            Self::BatchGasLimitExceeded { gas_limit, remaining } => {
+                write!(f, "gas limit {gas_limit} exceeds the {remaining} gas left in the batch")
+            }
+            Self::InsufficientBalanceForValue { balance, value } => {
+                write!(f, "balance {balance} cannot cover value {value}")
+            }
+            Self::InvalidEncoding => write!(f, "invalid transaction encoding"),
+            Self::NonceOverflow { nonce } => write!(f, "nonce {nonce} cannot be incremented"),
+            Self::BalanceOverflow { address } => write!(f, "balance of {address} would overflow"),
+            Self::BalanceUnderflow { address } => write!(f, "balance of {address} would underflow"),
+            Self::GasOverflow => write!(f, "gas or fee amount overflows"),
+            Self::ExecutionReverted => write!(f, "execution reverted"),
+            Self::ExecutionHalted => write!(f, "execution halted"),
+            Self::EvmRejected => write!(f, "rejected by the EVM"),
+        }
+    }
+}
+
+/// Why the pre-state witness cannot be used to execute a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StateError {
+    /// An account proof does not verify against the claimed state root.
+    InvalidAccountProof { address: Address },
+    /// Executing the batch needs a trie node the witness did not include.
+    MissingTrieNode,
+    AccountNotInWitness { address: Address },
+    InvalidTrieNode,
+    InvalidAccountEncoding,
+    /// An account to execute has code the witness did not include.
+    CodeNotInWitness { code_hash: B256 },
+    StorageNotInWitness { address: Address, slot: U256 },
+    /// The witness rebuilds a trie with a different root than the one claimed.
+    WitnessRootMismatch { claimed: B256, computed: B256 },
+}
+
+impl StateError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidAccountProof { .. } => ErrorCode::InvalidAccountProof,
+            Self::MissingTrieNode => ErrorCode::MissingTrieNode,
+            Self::AccountNotInWitness { .. } => ErrorCode::AccountNotInWitness,
+            Self::InvalidTrieNode => ErrorCode::InvalidTrieNode,
+            Self::InvalidAccountEncoding => ErrorCode::InvalidAccountEncoding,
+            Self::CodeNotInWitness { .. } => ErrorCode::CodeNotInWitness,
+            Self::StorageNotInWitness { .. } => ErrorCode::StorageNotInWitness,
+            Self::WitnessRootMismatch { .. } => ErrorCode::WitnessRootMismatch,
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidAccountProof { address } => {
+                write!(f, "proof for {address} does not verify against the state root")
+            }
+            Self::MissingTrieNode => write!(f, "trie node missing from witness"),
+            Self::AccountNotInWitness { address } => write!(f, "account {address} not covered by state witness"),
+            Self::InvalidTrieNode => write!(f, "invalid trie node"),
+            Self::InvalidAccountEncoding => write!(f, "invalid account encoding"),
+            Self::CodeNotInWitness { code_hash } => write!(f, "code {code_hash} not covered by witness"),
+            Self::StorageNotInWitness { address, slot } => {
+                write!(f, "storage slot {slot} of {address} not covered by witness")
+            }
+            Self::WitnessRootMismatch { claimed, computed } => {
+                write!(f, "witness has root {computed} instead of {claimed}")
+            }
+        }
+    }
+}
+
+/// Why `execute_transaction` did not produce a receipt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionError {
+    /// The transaction is rejected; the batch carries on without it.
+    Transaction(TransactionError),
+    /// The witness does not cover what the transaction touches.
+    State(StateError),
+}
+
+impl From<TransactionError> for ExecutionError {
+    fn from(error: TransactionError) -> Self {
+        Self::Transaction(error)
+    }
+}
+
+impl From<StateError> for ExecutionError {
+    fn from(error: StateError) -> Self {
+        Self::State(error)
+    }
+}
+
+/// Any failure of the state transition function, on either side of the zkVM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Error {
+    /// The transaction at `index` in the batch was rejected or failed.
+    Transaction { index: usize, error: TransactionError },
+    State(StateError),
+    MalformedInput,
+    /// The batch does not lead to the post-state root it claims.
+    StateRootMismatch { claimed: B256, computed: B256 },
+}
+
+impl Error {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Transaction { error, .. } => error.code(),
+            Self::State(error) => error.code(),
+            Self::MalformedInput => ErrorCode::MalformedInput,
+            Self::StateRootMismatch { .. } => ErrorCode::StateRootMismatch,
+        }
+    }
+}
+
+impl From<StateError> for Error {
+    fn from(error: StateError) -> Self {
+        Self::State(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transaction { index, error } => write!(f, "transaction {index}: {error}"),
+            Self::State(error) => write!(f, "{error}"),
+            Self::MalformedInput => write!(f, "malformed guest input"),
+            Self::StateRootMismatch { claimed, computed } => {
+                write!(f, "computed post-state root {computed} does not match claimed {claimed}")
+            }
+        }?;
+        write!(f, " (error {})", self.code() as u16)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
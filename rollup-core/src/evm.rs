@@ -0,0 +1,620 @@
+use alloc::collections::btree_map::Entry;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use revm::precompile::{PrecompileSpecId, Precompiles};
+use revm::primitives::{AccountInfo, Bytecode, EVMError, ExecutionResult, ResultAndState, SpecId};
+use revm::{DatabaseRef, Evm};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExecutionError, StateError, TransactionError};
+use crate::mpt::{EMPTY_ROOT_HASH, KECCAK_EMPTY};
+use crate::receipt::{Log, TransactionReceipt};
+use crate::storage::{StorageProof, StorageTrie};
+use crate::transaction::Transaction;
+use crate::{account_index, fee, AccountState, BatchEnv, BATCH_GAS_LIMIT};
+
+/// Contract data a batch needs beyond the accounts its transactions name, found
+/// by the host executing the batch natively.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractWitness {
+    /// Accounts only reached from contract code, proven by the state witness
+    /// like the accounts of `touched_addresses`. An account missing here is
+    /// still read from the state witness when code reaches it, at the cost of
+    /// running the transaction again.
+    pub addresses: Vec<Address>,
+    /// Bytecode of every contract executed.
+    pub code: Vec<Bytes>,
+    /// Storage trie nodes for every slot accessed, per account.
+    pub storage: Vec<StorageProof>,
+}
+
+/// Accounts and storage slots a batch accessed, recorded while executing it
+/// natively so the host can cut the witness out of the full state.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Accesses {
+    /// Every account the EVM loaded. Those outside `touched_addresses` are
+    /// what `ContractWitness::addresses` lists.
+    pub addresses: BTreeSet<Address>,
+    /// Every slot read or written, per account.
+    pub slots: BTreeMap<Address, BTreeSet<U256>>,
+    /// Slots set to zero at some point, whose removal may merge trie nodes
+    /// next to them.
+    pub cleared: BTreeMap<Address, BTreeSet<U256>>,
+    /// Code of every contract the batch deployed, which later batches calling
+    /// it need in their witness.
+    pub deployed: Vec<Bytes>,
+}
+
+impl Accesses {
+    /// Proofs of every accessed slot from the full pre-state `storage`.
+    /// Accounts missing from `storage` have none and need no proof.
+    pub fn storage_proofs(&self, storage: &BTreeMap<Address, StorageTrie>) -> Vec<StorageProof> {
+        self.slots
+            .iter()
+            .filter_map(|(address, slots)| {
+                let cleared = self.cleared.get(address).cloned().unwrap_or_default();
+                let nodes = storage.get(address)?.prove(slots, &cleared);
+                Some(StorageProof { address: *address, nodes })
+            })
+            .collect()
+    }
+}
+
+/// Code and storage of the batch as it executes.
+///
+/// Code is keyed by its own hash, so bytecode supplied by the host is only ever
+/// found under the `code_hash` it hashes to and cannot stand in for the code
+/// an account commits to. Storage tries are opened against
+/// the `storage_root` of each account, so slots outside the witness cannot be
+/// read or written.
+#[derive(Debug, Clone, Default)]
+pub struct ContractState {
+    code: BTreeMap<B256, Bytes>,
+    storage: BTreeMap<Address, StorageTrie>,
+    /// Storage proofs of accounts not covered yet, opened by `cover`.
+    proofs: BTreeMap<Address, Vec<Bytes>>,
+    /// Addresses the state witness covers; those without an account do not exist.
+    covered: BTreeSet<Address>,
+    accesses: Accesses,
+}
+
+impl ContractState {
+    /// `accounts` are the covered accounts as proven by the state witness.
+    pub fn new(
+        witness: &ContractWitness,
+        covered: BTreeSet<Address>,
+        accounts: &[AccountState],
+    ) -> Result<Self, StateError> {
+        let mut contracts = Self {
+            code: witness.code.iter().map(|code| (keccak256(code), code.clone())).collect(),
+            proofs: witness.storage.iter().map(|proof| (proof.address, proof.nodes.clone())).collect(),
+            ..Self::default()
+        };
+        for address in covered {
+            let account = accounts.iter().find(|a| a.address == address);
+            contracts.cover(address, account)?;
+        }
+        Ok(contracts)
+    }
+
+    /// Adds `address` to the covered accounts, `account` being its state as
+    /// proven by the state witness, and opens its storage proof if any.
+    pub fn cover(&mut self, address: Address, account: Option<&AccountState>) -> Result<(), StateError> {
+        if !self.covered.insert(address) {
+            return Err(StateError::AccountNotInWitness { address });
+        }
+        if let Some(nodes) = self.proofs.remove(&address) {
+            let root = account.map_or(EMPTY_ROOT_HASH, |account| account.storage_root);
+            self.storage.insert(address, StorageTrie::open(root, &nodes)?);
+        }
+        Ok(())
+    }
+
+    /// Value of `slot` for the account at `address`, whose storage trie has
+    /// root `storage_root`.
+    pub fn storage(&self, address: Address, storage_root: B256, slot: U256) -> Result<U256, StateError> {
+        let value = match self.storage.get(&address) {
+            Some(storage) => storage.get(slot),
+            None => StorageTrie::open(storage_root, &[])?.get(slot),
+        };
+        value.map_err(|error| not_in_witness(error, address, slot))
+    }
+
+    /// Writes `slot` and returns the new storage root.
+    fn set_storage(
+        &mut self,
+        address: Address,
+        storage_root: B256,
+        slot: U256,
+        value: U256,
+    ) -> Result<B256, StateError> {
+        let storage = match self.storage.entry(address) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(StorageTrie::open(storage_root, &[])?),
+        };
+        storage.set(slot, value).map_err(|error| not_in_witness(error, address, slot))?;
+        Ok(storage.root())
+    }
+
+    pub fn into_accesses(self) -> Accesses {
+        self.accesses
+    }
+}
+
+fn not_in_witness(error: StateError, address: Address, slot: U256) -> StateError {
+    match error {
+        StateError::MissingTrieNode => StateError::StorageNotInWitness { address, slot },
+        error => error,
+    }
+}
+
+/// Fork every batch executes under.
+const SPEC: SpecId = SpecId::CANCUN;
+
+/// Whether `address` holds a precompile, which runs in the EVM like contract
+/// code but has none deployed.
+pub(crate) fn is_precompile(address: &Address) -> bool {
+    Precompiles::new(PrecompileSpecId::from_spec_id(SPEC)).contains(address)
+}
+
+/// Runs a call into contract code or a contract creation. The EVM charges the
+/// gas, refunds what is left and pays the tip itself; the caller has already
+/// checked that the sender can pay for gas and value.
+///
+/// Creations deploy to `Transaction::create_address`, and `CREATE2` from
+/// contract code to the address derived from its salt; either must be covered
+/// by the state witness, as a proof of absence.
+pub(crate) fn transact(
+    tx: &Transaction,
+    accounts: &mut Vec<AccountState>,
+    contracts: &mut ContractState,
+    env: &BatchEnv,
+    gas_price: u64,
+) -> Result<TransactionReceipt, ExecutionError> {
+    // The EVM saturates rather than fail when paying the tip.
+    let max_tip = fee(tx.gas_limit(), gas_price - env.base_fee);
+    let fee_recipient = accounts.iter().find(|a| a.address == env.fee_recipient);
+    if fee_recipient.is_some_and(|account| account.balance.checked_add(max_tip).is_none()) {
+        return Err(TransactionError::BalanceOverflow { address: env.fee_recipient }.into());
+    }
+
+    let ResultAndState { result, state } = {
+        let mut evm = Evm::builder()
+            .with_ref_db(WitnessDb { accounts: accounts.as_slice(), contracts: &*contracts })
+            .with_spec_id(SPEC)
+            .modify_cfg_env(|cfg| cfg.chain_id = env.chain_id)
+            .modify_block_env(|block| {
+                block.coinbase = env.fee_recipient;
+                block.basefee = U256::from(env.base_fee);
+                block.gas_limit = U256::from(BATCH_GAS_LIMIT);
+            })
+            .modify_tx_env(|tx_env| {
+                tx_env.caller = tx.from;
+                tx_env.transact_to = tx.to();
+                tx_env.value = tx.value();
+                tx_env.data = tx.input().clone();
+                tx_env.gas_limit = tx.gas_limit();
+                tx_env.gas_price = U256::from(gas_price);
+                tx_env.nonce = Some(tx.nonce());
+                tx_env.chain_id = Some(env.chain_id);
+                tx_env.access_list = tx
+                    .access_list()
+                    .iter()
+                    .map(|item| revm::primitives::AccessListItem {
+                        address: item.address,
+                        storage_keys: item.storage_keys.clone(),
+                    })
+                    .collect();
+            })
+            .build();
+        evm.transact().map_err(|error| match error {
+            EVMError::Database(error) => ExecutionError::State(error),
+            _ => ExecutionError::Transaction(TransactionError::EvmRejected),
+        })?
+    };
+
+    contracts.accesses.addresses.extend(state.keys());
+    for (address, account) in state {
+        if !account.storage.is_empty() {
+            contracts.accesses.slots.entry(address).or_default().extend(account.storage.keys());
+        }
+        if !account.is_touched() {
+            continue;
+        }
+        let idx = account_index(accounts, address);
+        if account.is_selfdestructed() {
+            accounts[idx] = AccountState::new(address);
+            contracts.storage.insert(address, StorageTrie::new());
+            continue;
+        }
+        if account.is_created() {
+            // Deployment starts from empty storage whatever the address held.
+            accounts[idx].storage_root = EMPTY_ROOT_HASH;
+            contracts.storage.insert(address, StorageTrie::new());
+            let code = account.info.code.as_ref().map(Bytecode::original_bytes).unwrap_or_default();
+            if !code.is_empty() {
+                contracts.code.insert(account.info.code_hash, code.clone());
+                contracts.accesses.deployed.push(code);
+            }
+        }
+        accounts[idx].balance = account.info.balance;
+        accounts[idx].nonce = account.info.nonce;
+        accounts[idx].code_hash = account.info.code_hash;
+        for (slot, value) in account.storage {
+            if !value.is_changed() {
+                continue;
+            }
+            if value.present_value.is_zero() {
+                contracts.accesses.cleared.entry(address).or_default().insert(slot);
+            }
+            let storage_root = accounts[idx].storage_root;
+            accounts[idx].storage_root = contracts.set_storage(address, storage_root, slot, value.present_value)?;
+        }
+    }
+
+    let logs = result
+        .logs()
+        .iter()
+        .map(|log| Log { address: log.address, topics: log.topics().to_vec(), data: log.data.data.clone() })
+        .collect();
+    let mut receipt = match result {
+        ExecutionResult::Success { gas_used, .. } => TransactionReceipt::success(gas_used),
+        ExecutionResult::Revert { gas_used, .. } => {
+            TransactionReceipt::failed(TransactionError::ExecutionReverted, gas_used)
+        }
+        ExecutionResult::Halt { gas_used, .. } => {
+            TransactionReceipt::failed(TransactionError::ExecutionHalted, gas_used)
+        }
+    };
+    receipt.logs = logs;
+    Ok(receipt)
+}
+
+/// Read-only view of the batch state for revm. Anything the witness does not
+/// cover is an error rather than a default, so an incomplete witness cannot
+/// change the outcome of execution.
+struct WitnessDb<'a> {
+    accounts: &'a [AccountState],
+    contracts: &'a ContractState,
+}
+
+impl WitnessDb<'_> {
+    fn account(&self, address: Address) -> Option<&AccountState> {
+        self.accounts.iter().find(|a| a.address == address)
+    }
+}
+
+impl DatabaseRef for WitnessDb<'_> {
+    type Error = StateError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, StateError> {
+        match self.account(address) {
+            Some(account) => Ok(Some(AccountInfo {
+                balance: account.balance,
+                nonce: account.nonce,
+                code_hash: account.code_hash,
+                code: None,
+            })),
+            None if self.contracts.covered.contains(&address) => Ok(None),
+            None => Err(StateError::AccountNotInWitness { address }),
+        }
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, StateError> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::new());
+        }
+        self.contracts
+            .code
+            .get(&code_hash)
+            .map(|code| Bytecode::new_raw(code.clone()))
+            .ok_or(StateError::CodeNotInWitness { code_hash })
+    }
+
+    fn storage_ref(&self, address: Address, slot: U256) -> Result<U256, StateError> {
+        let storage_root = self.account(address).map_or(EMPTY_ROOT_HASH, |account| account.storage_root);
+        self.contracts.storage(address, storage_root, slot)
+    }
+
+    /// Batches are not chained by block hash, so `BLOCKHASH` reads zero.
+    fn block_hash_ref(&self, _number: u64) -> Result<B256, StateError> {
+        Ok(B256::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execute_transaction;
+    use crate::receipt::TransactionStatus;
+    use crate::tests::{env, sender_key};
+    use crate::transaction::TxEip1559;
+    use alloy_primitives::{b256, TxKind};
+    use k256::ecdsa::SigningKey;
+
+    /// Stores the call value in slot 0 and logs topic 0x2a.
+    const STORE_AND_LOG: [u8; 12] = [0x34, 0x60, 0x00, 0x55, 0x60, 0x2a, 0x60, 0x00, 0x60, 0x00, 0xa1, 0x00];
+    /// Reverts without data.
+    const REVERT: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+    /// Init code deploying `REVERT`.
+    const DEPLOY_REVERT: [u8; 14] = [0x64, 0x60, 0x00, 0x60, 0x00, 0xfd, 0x60, 0x00, 0x52, 0x60, 0x05, 0x60, 0x1b, 0xf3];
+    /// Creates an empty contract with `CREATE2` and salt 0x2a.
+    const CREATE2_EMPTY: [u8; 10] = [0x60, 0x2a, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0xf5, 0x00];
+
+    fn contract() -> Address {
+        Address::repeat_byte(0xc0)
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    /// Calls `callee` without value or data.
+    fn call_code(callee: Address) -> Vec<u8> {
+        let mut code = [0x60, 0x00].repeat(5);
+        code.push(0x73);
+        code.extend_from_slice(callee.as_slice());
+        code.extend_from_slice(&[0x5a, 0xf1, 0x00]);
+        code
+    }
+
+    /// A signed dynamic fee transaction with room for contract execution.
+    fn transaction(key: &SigningKey, to: TxKind, nonce: u64, value: u64, input: &[u8]) -> Transaction {
+        let tx = TxEip1559 {
+            chain_id: 1,
+            nonce,
+            max_priority_fee_per_gas: 1,
+            max_fee_per_gas: 1,
+            gas_limit: 100_000,
+            to,
+            value: U256::from(value),
+            input: Bytes::copy_from_slice(input),
+            access_list: Vec::new(),
+        };
+        Transaction::sign(key, tx)
+    }
+
+    fn call(key: &SigningKey, value: u64) -> Transaction {
+        transaction(key, TxKind::Call(contract()), 0, value, &[])
+    }
+
+    /// A funded sender and a contract running `code`, with `code` in the witness.
+    fn setup(code: &[u8]) -> (SigningKey, Vec<AccountState>, ContractState) {
+        let key = sender_key();
+        let code = Bytes::copy_from_slice(code);
+        let accounts = vec![
+            AccountState { balance: U256::from(1_000_000), ..AccountState::new(Address::from_private_key(&key)) },
+            AccountState { code_hash: keccak256(&code), ..AccountState::new(contract()) },
+        ];
+        let witness = ContractWitness { code: vec![code], ..ContractWitness::default() };
+        let contracts = ContractState::new(&witness, [env().fee_recipient].into(), &accounts).unwrap();
+        (key, accounts, contracts)
+    }
+
+    #[test]
+    fn contract_call_writes_storage_and_logs() {
+        let (key, mut accounts, mut contracts) = setup(&STORE_AND_LOG);
+
+        let receipt = execute_transaction(&call(&key, 100), &mut accounts, &env(), &mut contracts).unwrap();
+
+        assert_eq!(receipt.status, TransactionStatus::Success);
+        assert_eq!(receipt.gas_used, 43_864);
+        let log = Log { address: contract(), topics: vec![B256::with_last_byte(0x2a)], data: Bytes::new() };
+        assert_eq!(receipt.logs, vec![log]);
+        let storage_root = b256!("52fa3cc6870cf8c957d654cf4e972604d2a48306f2d34293950b043d54e60f0a");
+        assert_eq!(accounts[1].storage_root, storage_root);
+        assert_eq!(contracts.storage(contract(), storage_root, U256::ZERO), Ok(U256::from(100)));
+        assert_eq!(accounts[0].nonce, 1);
+        assert_eq!(accounts[0].balance, U256::from(1_000_000 - 100 - 43_864));
+        assert_eq!(accounts[1].balance, U256::from(100));
+        let fee_recipient = accounts.iter().find(|a| a.address == env().fee_recipient).unwrap();
+        assert_eq!(fee_recipient.balance, U256::from(43_864));
+    }
+
+    #[test]
+    fn reverted_call_pays_gas_and_keeps_value() {
+        let (key, mut accounts, mut contracts) = setup(&REVERT);
+
+        let receipt = execute_transaction(&call(&key, 100), &mut accounts, &env(), &mut contracts).unwrap();
+
+        assert_eq!(receipt, TransactionReceipt::failed(TransactionError::ExecutionReverted, 21_006));
+        assert_eq!(accounts[0].nonce, 1);
+        assert_eq!(accounts[0].balance, U256::from(1_000_000 - 21_006));
+        assert_eq!(accounts[1].balance, U256::ZERO);
+    }
+
+    #[test]
+    fn code_missing_from_witness_is_a_state_error() {
+        let (key, mut accounts, _) = setup(&STORE_AND_LOG);
+        let code_hash = accounts[1].code_hash;
+        let mut contracts =
+            ContractState::new(&ContractWitness::default(), [env().fee_recipient].into(), &accounts).unwrap();
+
+        assert_eq!(
+            execute_transaction(&call(&key, 100), &mut accounts, &env(), &mut contracts),
+            Err(ExecutionError::State(StateError::CodeNotInWitness { code_hash }))
+        );
+        assert_eq!(accounts[0].nonce, 0);
+    }
+
+    #[test]
+    fn bytecode_must_hash_to_code_hash() {
+        let (key, mut accounts, _) = setup(&STORE_AND_LOG);
+        let witness = ContractWitness { code: vec![Bytes::copy_from_slice(&REVERT)], ..ContractWitness::default() };
+        let mut contracts = ContractState::new(&witness, [env().fee_recipient].into(), &accounts).unwrap();
+
+        assert_eq!(
+            execute_transaction(&call(&key, 100), &mut accounts, &env(), &mut contracts),
+            Err(ExecutionError::State(StateError::CodeNotInWitness { code_hash: keccak256(STORE_AND_LOG) }))
+        );
+    }
+
+    #[test]
+    fn creation_deploys_code_later_calls_run() {
+        let (key, mut accounts, _) = setup(&STORE_AND_LOG);
+        let create = transaction(&key, TxKind::Create, 0, 0, &DEPLOY_REVERT);
+        let deployed = create.create_address().unwrap();
+        let covered = [env().fee_recipient, deployed].into();
+        let mut contracts = ContractState::new(&ContractWitness::default(), covered, &accounts).unwrap();
+
+        let receipt = execute_transaction(&create, &mut accounts, &env(), &mut contracts).unwrap();
+
+        assert_eq!(receipt.status, TransactionStatus::Success);
+        let account = accounts.iter().find(|a| a.address == deployed).unwrap();
+        assert_eq!(account.code_hash, keccak256(REVERT));
+        assert_eq!(account.nonce, 1);
+        assert_eq!(contracts.accesses.deployed, vec![Bytes::copy_from_slice(&REVERT)]);
+
+        let call = transaction(&key, TxKind::Call(deployed), 1, 0, &[]);
+        let receipt = execute_transaction(&call, &mut accounts, &env(), &mut contracts).unwrap();
+        assert_eq!(receipt.error, Some(TransactionError::ExecutionReverted));
+    }
+
+    #[test]
+    fn create2_deploys_to_salted_address() {
+        let (key, mut accounts, _) = setup(&CREATE2_EMPTY);
+        let created = contract().create2_from_code(B256::with_last_byte(0x2a), Bytes::new());
+        let witness = ContractWitness { code: vec![Bytes::copy_from_slice(&CREATE2_EMPTY)], ..ContractWitness::default() };
+        let mut contracts = ContractState::new(&witness, [env().fee_recipient, created].into(), &accounts).unwrap();
+
+        let receipt = execute_transaction(&call(&key, 0), &mut accounts, &env(), &mut contracts).unwrap();
+
+        assert_eq!(receipt.status, TransactionStatus::Success);
+        assert_eq!(accounts[1].nonce, 1);
+        let account = accounts.iter().find(|a| a.address == created).unwrap();
+        assert_eq!(account, &AccountState { nonce: 1, ..AccountState::new(created) });
+    }
+
+    #[test]
+    fn storage_missing_from_witness_is_a_state_error() {
+        let (key, mut accounts, mut contracts) = setup(&STORE_AND_LOG);
+        accounts[1].storage_root = StorageTrie::from_slots(&[(U256::ZERO, U256::from(5))]).root();
+
+        assert_eq!(
+            execute_transaction(&call(&key, 100), &mut accounts, &env(), &mut contracts),
+            Err(ExecutionError::State(StateError::StorageNotInWitness { address: contract(), slot: U256::ZERO }))
+        );
+        assert_eq!(accounts[0].nonce, 0);
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    fn batch(old_state_root: B256, transactions: Vec<Transaction>) -> crate::StateTransition {
+        crate::StateTransition {
+            chain_id: 1,
+            transactions,
+            old_state_root,
+            new_state_root: B256::ZERO,
+            batch_index: 0,
+            fee_recipient: env().fee_recipient,
+            base_fee: 0,
+        }
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn native_run_finds_accounts_reached_from_code() {
+        use crate::mpt::StateTrie;
+        use crate::state::StateWitness;
+        use crate::{apply_batch, touched_addresses, trace_batch};
+
+        let callee = Address::repeat_byte(0xc1);
+        let code = call_code(callee);
+        let (key, mut accounts, _) = setup(&code);
+        accounts.push(AccountState { balance: U256::from(5), ..AccountState::new(callee) });
+        let genesis = StateTrie::from_accounts(&accounts);
+        let transition = batch(genesis.root(), vec![call(&key, 0)]);
+        let code = vec![Bytes::from(code)];
+
+        // Natively, without knowing the callee up front.
+        let mut full = genesis.clone();
+        let native = ContractWitness { code: code.clone(), ..ContractWitness::default() };
+        let (receipts, accesses) = trace_batch(&transition, &mut full, &native).unwrap();
+        assert_eq!(receipts[0].status, TransactionStatus::Success);
+        assert!(accesses.addresses.contains(&callee));
+
+        // In the guest, over the accounts the native run accessed.
+        let mut addresses = touched_addresses(&transition);
+        let nodes = |addresses: &BTreeSet<Address>| addresses.iter().flat_map(|a| genesis.prove(a)).collect();
+        let contracts = ContractWitness {
+            addresses: accesses.addresses.difference(&addresses).copied().collect(),
+            code,
+            storage: Vec::new(),
+        };
+        assert_eq!(contracts.addresses, vec![callee]);
+
+        let mut witness = StateTrie::open(genesis.root(), nodes(&addresses)).unwrap();
+        assert_eq!(
+            apply_batch(&transition, &mut witness, &contracts),
+            Err(crate::error::Error::State(StateError::AccountNotInWitness { address: callee }))
+        );
+
+        addresses.extend(accesses.addresses);
+        let mut witness = StateTrie::open(genesis.root(), nodes(&addresses)).unwrap();
+        apply_batch(&transition, &mut witness, &contracts).unwrap();
+        assert_eq!(witness.root(), full.root());
+
+        // Without the hint the guest still gets there, reading the callee once reached.
+        let mut witness = StateTrie::open(genesis.root(), nodes(&addresses)).unwrap();
+        apply_batch(&transition, &mut witness, &ContractWitness { addresses: Vec::new(), ..contracts }).unwrap();
+        assert_eq!(witness.root(), full.root());
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn self_destruct_in_creation_leaves_no_account() {
+        use crate::mpt::StateTrie;
+        use crate::state::StateWitness;
+        use crate::trace_batch;
+
+        let (key, accounts, _) = setup(&STORE_AND_LOG);
+        let mut state = StateTrie::from_accounts(&accounts);
+        // `SELFDESTRUCT` to the caller from the init code.
+        let create = transaction(&key, TxKind::Create, 0, 0, &[0x33, 0xff]);
+        let created = create.create_address().unwrap();
+        let transition = batch(state.root(), vec![create]);
+
+        let (receipts, _) = trace_batch(&transition, &mut state, &ContractWitness::default()).unwrap();
+
+        assert_eq!(receipts[0].status, TransactionStatus::Success);
+        assert_eq!(state.get(&created), Ok(None));
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn storage_witness_matches_full_storage() {
+        use crate::mpt::StateTrie;
+        use crate::state::StateWitness;
+        use crate::{apply_batch, touched_addresses, trace_batch};
+
+        let (key, mut accounts, _) = setup(&STORE_AND_LOG);
+        let storage = StorageTrie::from_slots(&[(U256::ZERO, U256::from(5)), (U256::from(1), U256::from(7))]);
+        accounts[1].storage_root = storage.root();
+        let genesis = StateTrie::from_accounts(&accounts);
+        let transition = batch(genesis.root(), vec![call(&key, 0)]);
+        let code = vec![Bytes::copy_from_slice(&STORE_AND_LOG)];
+
+        // Natively, over every node of the contract's storage.
+        let mut full = genesis.clone();
+        let native = ContractWitness {
+            code: code.clone(),
+            storage: vec![StorageProof { address: contract(), nodes: storage.nodes() }],
+            ..ContractWitness::default()
+        };
+        let (receipts, accesses) = trace_batch(&transition, &mut full, &native).unwrap();
+        assert_eq!(receipts[0].status, TransactionStatus::Success);
+        assert_eq!(accesses.cleared, BTreeMap::from([(contract(), BTreeSet::from([U256::ZERO]))]));
+
+        // In the guest, over the slots the native run accessed.
+        let nodes = touched_addresses(&transition).iter().flat_map(|a| genesis.prove(a)).collect();
+        let mut witness = StateTrie::open(genesis.root(), nodes).unwrap();
+        let contracts = ContractWitness {
+            code,
+            storage: accesses.storage_proofs(&BTreeMap::from([(contract(), storage)])),
+            ..ContractWitness::default()
+        };
+        apply_batch(&transition, &mut witness, &contracts).unwrap();
+
+        assert_eq!(witness.root(), full.root());
+        let account = witness.get(&contract()).unwrap().unwrap();
+        assert_eq!(account.storage_root, b256!("2e7827dc2c61c322f13f77e6f25dd18844ccc48426dde70301d2d57d138fced8"));
+    }
+}
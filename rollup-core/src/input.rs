@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::error::Error;
+use crate::evm::ContractWitness;
+use crate::state::{State, StateWitness};
+use crate::StateTransition;
+
+/// Everything the guest reads: the batch, the pre-state witness for every
+/// account it touches, proven against `transition.old_state_root`, and the code
+/// and storage its contract calls need.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestInput {
+    pub transition: StateTransition,
+    pub witness: <State as StateWitness>::Proof,
+    pub contracts: ContractWitness,
+}
+
+/// The encodings need the standard library; `no_std` users bring their own
+/// serde format.
+#[cfg(feature = "std")]
+impl GuestInput {
+    /// Compact binary encoding read by the guest through `sp1_zkvm::io::read`.
+    ///
+    /// Hashes, addresses and amounts are written as raw bytes rather than hex
+    /// strings, which is what makes decoding cheap in the zkVM.
+    pub fn to_binary(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("Failed to encode guest input")
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, Error> {
+        bincode::deserialize(bytes).map_err(|_| Error::MalformedInput)
+    }
+
+    /// JSON encoding read by guests built with the `json-input` feature.
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("Failed to encode guest input")
+    }
+
+    pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
+        serde_json::from_slice(bytes).map_err(|_| Error::MalformedInput)
+    }
+}
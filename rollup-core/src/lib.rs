@@ -0,0 +1,1109 @@
+//! Types and state transition function of the rollup, shared by the SP1 guest
+//! proving batches and the native host preparing them.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+use alloc::{vec, vec::Vec};
+use alloy_primitives::{keccak256, Address, TxKind, B256, U256};
+use alloy_rlp::{BufMut, Encodable, Decodable, Header};
+use core::cmp::Ordering;
+use serde::{Deserialize, Serialize};
+
+/// Gas charged for every transaction before any of its data is considered.
+pub const TX_BASE_GAS: u64 = 21_000;
+/// Gas per zero byte of transaction data.
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+/// Gas per non-zero byte of transaction data.
+pub const TX_DATA_NON_ZERO_GAS: u64 = 16;
+/// Gas per address in an EIP-2930 access list.
+pub const TX_ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+/// Gas per storage key in an EIP-2930 access list.
+pub const TX_ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+/// Extra gas for a transaction that creates a contract.
+pub const TX_CREATE_GAS: u64 = 32_000;
+/// Gas per 32-byte word of init code (EIP-3860).
+pub const INITCODE_WORD_GAS: u64 = 2;
+
+/// Maximum gas a single batch may use.
+pub const BATCH_GAS_LIMIT: u64 = 30_000_000;
+/// Ratio between the batch gas limit and the gas target the base fee steers towards.
+pub const ELASTICITY_MULTIPLIER: u64 = 2;
+/// Bounds the base fee change between consecutive batches to 1/8.
+pub const BASE_FEE_MAX_CHANGE_DENOMINATOR: u64 = 8;
+
+pub mod error;
+pub mod evm;
+pub mod input;
+pub mod mpt;
+pub mod public_values;
+pub mod receipt;
+pub mod smt;
+pub mod state;
+pub mod storage;
+pub mod transaction;
+pub mod tx_root;
+
+use error::{Error, ExecutionError, StateError, TransactionError};
+use evm::{Accesses, ContractState, ContractWitness};
+use mpt::{EMPTY_ROOT_HASH, KECCAK_EMPTY};
+use input::GuestInput;
+use receipt::TransactionReceipt;
+use state::{State, StateWitness};
+use transaction::Transaction;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: u64,
+    pub code_hash: B256,
+    pub storage_root: B256,
+}
+
+impl AccountState {
+    /// Account as it exists before anything has been sent to it.
+    pub fn new(address: Address) -> Self {
+        Self {
+            address,
+            balance: U256::ZERO,
+            nonce: 0,
+            code_hash: KECCAK_EMPTY,
+            storage_root: EMPTY_ROOT_HASH,
+        }
+    }
+
+    /// Empty as EIP-161 defines it: no nonce, balance or code. Such an account
+    /// is deleted from the state once touched.
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0 && self.balance.is_zero() && self.code_hash == KECCAK_EMPTY
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    /// Rollup deployment the batch belongs to; every transaction must be signed for it.
+    pub chain_id: u64,
+    pub transactions: Vec<Transaction>,
+    pub old_state_root: B256,
+    pub new_state_root: B256,
+    pub batch_index: u64,
+    /// Sequencer account credited with the fees paid by the batch.
+    pub fee_recipient: Address,
+    /// Base fee per gas for this batch, as derived from the previous batch.
+    pub base_fee: u64,
+}
+
+/// Batch-wide parameters every transaction in the batch executes against.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchEnv {
+    pub chain_id: u64,
+    pub fee_recipient: Address,
+    pub base_fee: u64,
+}
+
+pub fn hash_transaction(tx: &Transaction) -> B256 {
+    let mut encoded = Vec::new();
+    tx.encode(&mut encoded);
+    keccak256(&encoded)
+}
+
+/// Contract creations touch the address they deploy to.
+pub fn touched_addresses(transition: &StateTransition) -> BTreeSet<Address> {
+    let mut addresses: BTreeSet<Address> = transition
+        .transactions
+        .iter()
+        .flat_map(|tx| [Some(tx.from), tx.to().to().copied(), tx.create_address()])
+        .flatten()
+        .collect();
+    addresses.insert(transition.fee_recipient);
+    addresses
+}
+
+/// Index of `address` in `accounts`. Accounts missing from the pre-state have
+/// been proven absent by the witness, so they start out empty.
+fn account_index(accounts: &mut Vec<AccountState>, address: Address) -> usize {
+    match accounts.iter().position(|a| a.address == address) {
+        Some(idx) => idx,
+        None => {
+            accounts.push(AccountState::new(address));
+            accounts.len() - 1
+        }
+    }
+}
+
+/// `None` if the gas does not fit in a `u64`.
+pub fn intrinsic_gas(tx: &Transaction) -> Option<u64> {
+    let data = tx.input();
+    let zero_bytes = data.iter().filter(|&&byte| byte == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    
+    let access_list = tx.access_list();
+    let storage_keys = access_list
+        .iter()
+        .try_fold(0u64, |keys, item| keys.checked_add(item.storage_keys.len() as u64))?;
+    
+    let create_gas = match tx.to() {
+        TxKind::Call(_) => 0,
+        TxKind::Create => TX_CREATE_GAS.checked_add((data.len() as u64).div_ceil(32).checked_mul(INITCODE_WORD_GAS)?)?,
+    };
+    
+    TX_BASE_GAS
+        .checked_add(create_gas)?
+        .checked_add(zero_bytes.checked_mul(TX_DATA_ZERO_GAS)?)?
+        .checked_add(non_zero_bytes.checked_mul(TX_DATA_NON_ZERO_GAS)?)?
+        .checked_add((access_list.len() as u64).checked_mul(TX_ACCESS_LIST_ADDRESS_GAS)?)?
+        .checked_add(storage_keys.checked_mul(TX_ACCESS_LIST_STORAGE_KEY_GAS)?)
+}
+
+fn credit(account: &mut AccountState, amount: U256) -> Result<(), TransactionError> {
+    account.balance = account
+        .balance
+        .checked_add(amount)
+        .ok_or(TransactionError::BalanceOverflow { address: account.address })?;
+    Ok(())
+}
+
+fn debit(account: &mut AccountState, amount: U256) -> Result<(), TransactionError> {
+    account.balance = account
+        .balance
+        .checked_sub(amount)
+        .ok_or(TransactionError::BalanceUnderflow { address: account.address })?;
+    Ok(())
+}
+
+/// Gas times a per-gas price; the product of two `u64`s always fits in a `u128`.
+fn fee(gas: u64, price: u64) -> U256 {
+    U256::from(gas as u128 * price as u128)
+}
+
+/// Copies of the accounts a transaction may modify, taken before it runs so a
+/// rejection halfway through can be undone.
+struct Checkpoint {
+    len: usize,
+    saved: Vec<(usize, AccountState)>,
+}
+
+impl Checkpoint {
+    fn new(accounts: &[AccountState], addresses: &[Address]) -> Self {
+        let saved = accounts
+            .iter()
+            .enumerate()
+            .filter(|(_, account)| addresses.contains(&account.address))
+            .map(|(idx, account)| (idx, account.clone()))
+            .collect();
+        Self { len: accounts.len(), saved }
+    }
+
+    fn restore(self, accounts: &mut Vec<AccountState>) {
+        accounts.truncate(self.len);
+        for (idx, account) in self.saved {
+            accounts[idx] = account;
+        }
+    }
+}
+
+/// EIP-1559 base fee for the batch following one that used `gas_used` gas at
+/// `base_fee`.
+pub fn next_base_fee(base_fee: u64, gas_used: u64) -> u64 {
+    let gas_target = BATCH_GAS_LIMIT / ELASTICITY_MULTIPLIER;
+    let base_fee = u128::from(base_fee);
+    
+    match gas_used.cmp(&gas_target) {
+        Ordering::Equal => base_fee as u64,
+        Ordering::Greater => {
+            let gas_delta = u128::from(gas_used - gas_target);
+            let fee_delta = (base_fee * gas_delta / u128::from(gas_target)
+                / u128::from(BASE_FEE_MAX_CHANGE_DENOMINATOR))
+                .max(1);
+            u64::try_from(base_fee + fee_delta).unwrap_or(u64::MAX)
+        }
+        Ordering::Less => {
+            let gas_delta = u128::from(gas_target - gas_used);
+            let fee_delta = base_fee * gas_delta / u128::from(gas_target)
+                / u128::from(BASE_FEE_MAX_CHANGE_DENOMINATOR);
+            (base_fee - fee_delta) as u64
+        }
+    }
+}
+
+/// Applies `tx` to `accounts` and returns its receipt.
+///
+/// `ExecutionError::Transaction` means the transaction cannot be included and
+/// `accounts` is unchanged; `ExecutionError::State` means the witness does not
+/// cover it.
+///
+/// A transaction whose fee is paid but whose transfer cannot be made is
+/// included as failed rather than rejected.
+pub fn execute_transaction(
+    tx: &Transaction,
+    accounts: &mut Vec<AccountState>,
+    env: &BatchEnv,
+    contracts: &mut ContractState,
+) -> Result<TransactionReceipt, ExecutionError> {
+    // Transactions without a chain id (pre-EIP-155 legacy) could be replayed
+    // from any other chain and are refused along with foreign ones.
+    if tx.chain_id() != Some(env.chain_id) {
+        return Err(TransactionError::ChainIdMismatch { expected: env.chain_id, actual: tx.chain_id() }.into());
+    }
+    
+    let signer = tx.recover_signer()?;
+    if signer != tx.from {
+        return Err(TransactionError::SignerMismatch { claimed: tx.from, recovered: signer }.into());
+    }
+    
+    let from_idx = accounts.iter().position(|a| a.address == tx.from);
+    let from_idx = from_idx.ok_or(TransactionError::SenderNotFound { sender: tx.from })?;
+    
+    let expected = accounts[from_idx].nonce;
+    match tx.nonce().cmp(&expected) {
+        Ordering::Less => return Err(TransactionError::NonceTooLow { expected, actual: tx.nonce() }.into()),
+        Ordering::Greater => return Err(TransactionError::NonceTooHigh { expected, actual: tx.nonce() }.into()),
+        Ordering::Equal => {}
+    }
+    
+    let nonce = expected.checked_add(1).ok_or(TransactionError::NonceOverflow { nonce: expected })?;
+    
+    let gas_used = intrinsic_gas(tx).ok_or(TransactionError::GasOverflow)?;
+    if tx.gas_limit() < gas_used {
+        return Err(TransactionError::IntrinsicGasTooLow { gas_limit: tx.gas_limit(), intrinsic_gas: gas_used }.into());
+    }
+    
+    let max_fee_per_gas = tx.max_fee_per_gas();
+    let max_priority_fee_per_gas = tx.max_priority_fee_per_gas();
+    if max_priority_fee_per_gas > max_fee_per_gas {
+        return Err(TransactionError::PriorityFeeAboveMaxFee { max_priority_fee_per_gas, max_fee_per_gas }.into());
+    }
+    if max_fee_per_gas < env.base_fee {
+        return Err(TransactionError::MaxFeeBelowBaseFee { max_fee_per_gas, base_fee: env.base_fee }.into());
+    }
+    
+    // Affordability is checked against the fee cap, but only the effective
+    // price is charged.
+    let max_gas_cost = fee(tx.gas_limit(), max_fee_per_gas);
+    let balance = accounts[from_idx].balance;
+    if balance < max_gas_cost {
+        return Err(TransactionError::InsufficientFundsForGas { balance, max_gas_cost }.into());
+    }
+    
+    let priority_fee = max_priority_fee_per_gas.min(max_fee_per_gas - env.base_fee);
+    let gas_price = env.base_fee.checked_add(priority_fee).ok_or(TransactionError::GasOverflow)?;
+    let gas_cost = fee(tx.gas_limit(), gas_price);
+    // Transfers do no work beyond their intrinsic cost, so the rest of the
+    // prepaid gas goes back to the sender.
+    let refund = fee(tx.gas_limit() - gas_used, gas_price);
+    // The base fee portion is burned; only the tip reaches the fee recipient.
+    let tip = fee(gas_used, priority_fee);
+    
+    // Contract creations and calls into contract code or precompiles run in
+    // the EVM. Without the value none can start, which fails the transaction
+    // the same way as a transfer.
+    let runs_code = match tx.to() {
+        TxKind::Create => true,
+        TxKind::Call(to) => {
+            evm::is_precompile(&to) || accounts.iter().any(|a| a.address == to && a.code_hash != KECCAK_EMPTY)
+        }
+    };
+    if runs_code && accounts[from_idx].balance - gas_cost >= tx.value() {
+        return evm::transact(tx, accounts, contracts, env, gas_price);
+    }
+    
+    // Credits can still overflow against a pre-state with huge balances, in
+    // which case the transaction is rejected and its partial effects undone.
+    let mut modified = vec![tx.from, env.fee_recipient];
+    modified.extend(tx.to().to());
+    let checkpoint = Checkpoint::new(accounts, &modified);
+    let mut settle = || -> Result<TransactionReceipt, TransactionError> {
+        debit(&mut accounts[from_idx], gas_cost)?;
+        accounts[from_idx].nonce = nonce;
+        
+        let balance = accounts[from_idx].balance;
+        let receipt = match tx.to() {
+            TxKind::Call(to) if balance >= tx.value() => {
+                debit(&mut accounts[from_idx], tx.value())?;
+                let to_idx = account_index(accounts, to);
+                credit(&mut accounts[to_idx], tx.value())?;
+                TransactionReceipt::success(gas_used)
+            }
+            _ => {
+                let error = TransactionError::InsufficientBalanceForValue { balance, value: tx.value() };
+                TransactionReceipt::failed(error, gas_used)
+            }
+        };
+        
+        credit(&mut accounts[from_idx], refund)?;
+        let fee_recipient_idx = account_index(accounts, env.fee_recipient);
+        credit(&mut accounts[fee_recipient_idx], tip)?;
+        
+        Ok(receipt)
+    };
+    
+    let result = settle();
+    if result.is_err() {
+        checkpoint.restore(accounts);
+    }
+    result.map_err(ExecutionError::from)
+}
+
+/// Executes `transition` against `state`, leaving the post-state in it, and
+/// returns a receipt per transaction.
+///
+/// Rejected transactions are recorded and skipped, so only a witness that does
+/// not cover the batch makes this fail, with `Error::State`.
+///
+/// The guest runs this over the witness of the touched accounts; natively it can
+/// run over the full state to find the post-state root to claim.
+pub fn apply_batch<S: StateWitness>(
+    transition: &StateTransition,
+    state: &mut S,
+    contracts: &ContractWitness,
+) -> Result<Vec<TransactionReceipt>, Error> {
+    trace_batch(transition, state, contracts).map(|(receipts, _)| receipts)
+}
+
+/// `apply_batch`, also returning the accounts and storage slots the batch
+/// accessed. Run natively over the full state, these are what the host cuts the
+/// guest's witness from: `Accesses::addresses` for the state witness and
+/// `ContractWitness::addresses`, `Accesses::storage_proofs` for storage.
+pub fn trace_batch<S: StateWitness>(
+    transition: &StateTransition,
+    state: &mut S,
+    contracts: &ContractWitness,
+) -> Result<(Vec<TransactionReceipt>, Accesses), Error> {
+    let mut covered = touched_addresses(transition);
+    covered.extend(contracts.addresses.iter().copied());
+    let mut accounts = Vec::new();
+    for address in &covered {
+        if let Some(account) = proven_account(state, address)? {
+            accounts.push(account);
+        }
+    }
+    let mut contract_state = ContractState::new(contracts, covered, &accounts)?;
+
+    let env = BatchEnv {
+        chain_id: transition.chain_id,
+        fee_recipient: transition.fee_recipient,
+        base_fee: transition.base_fee,
+    };
+    
+    let mut gas_used = 0;
+    let mut receipts = Vec::with_capacity(transition.transactions.len());
+    for tx in &transition.transactions {
+        let remaining = BATCH_GAS_LIMIT - gas_used;
+        let mut receipt = if tx.gas_limit() > remaining {
+            TransactionReceipt::rejected(TransactionError::BatchGasLimitExceeded { gas_limit: tx.gas_limit(), remaining })
+        } else {
+            loop {
+                match execute_transaction(tx, &mut accounts, &env, &mut contract_state) {
+                    Ok(receipt) => break receipt,
+                    Err(ExecutionError::Transaction(error)) => break TransactionReceipt::rejected(error),
+                    // Contract code reached an account outside `covered` before
+                    // anything was written back, so the transaction runs again
+                    // once `state` has proven it.
+                    Err(ExecutionError::State(StateError::AccountNotInWitness { address })) => {
+                        let account = proven_account(state, &address)?;
+                        contract_state.cover(address, account.as_ref())?;
+                        accounts.extend(account);
+                    }
+                    Err(ExecutionError::State(error)) => return Err(error.into()),
+                }
+            }
+        };
+        gas_used += receipt.gas_used;
+        receipt.tx_type = tx.tx.tx_type();
+        receipt.cumulative_gas_used = gas_used;
+        receipts.push(receipt);
+    }
+    
+    // Destroyed accounts and accounts credited nothing come out empty, and are
+    // deleted rather than left behind as empty leaves. Unchanged accounts are
+    // skipped, so an empty one only read by the batch stays as it was.
+    for account in &accounts {
+        if proven_account(state, &account.address)?.as_ref() == Some(account) {
+            continue;
+        }
+        if account.is_empty() {
+            state.remove(&account.address)?;
+        } else {
+            state.update(account)?;
+        }
+    }
+    
+    Ok((receipts, contract_state.into_accesses()))
+}
+
+/// `state.get`, reporting an account the witness does not reach as such rather
+/// than as the trie node it lacks.
+fn proven_account<S: StateWitness>(state: &S, address: &Address) -> Result<Option<AccountState>, StateError> {
+    state.get(address).map_err(|error| match error {
+        StateError::MissingTrieNode => StateError::AccountNotInWitness { address: *address },
+        error => error,
+    })
+}
+
+/// Everything the guest does with its input: opens the witness, applies the
+/// batch and checks the post-state root it claims. The host runs the same
+/// function natively to learn the public values a proof will commit.
+pub fn execute_batch(input: GuestInput) -> Result<StateTransitionProof, Error> {
+    let GuestInput { transition, witness: proof, contracts } = input;
+    let mut witness = State::open(transition.old_state_root, proof)?;
+    let old_state_root = witness.root();
+    if old_state_root != transition.old_state_root {
+        return Err(StateError::WitnessRootMismatch { claimed: transition.old_state_root, computed: old_state_root }.into());
+    }
+    
+    let receipts = apply_batch(&transition, &mut witness, &contracts)?;
+    let gas_used = receipts.iter().map(|receipt| receipt.gas_used).sum();
+    
+    let new_state_root = witness.root();
+    if new_state_root != transition.new_state_root {
+        return Err(Error::StateRootMismatch { claimed: transition.new_state_root, computed: new_state_root });
+    }
+    
+    let transaction_hashes: Vec<B256> = transition.transactions.iter().map(hash_transaction).collect();
+    Ok(StateTransitionProof {
+        chain_id: transition.chain_id,
+        old_state_root,
+        new_state_root,
+        batch_index: transition.batch_index,
+        transaction_count: transition.transactions.len() as u64,
+        gas_used,
+        base_fee: transition.base_fee,
+        next_base_fee: next_base_fee(transition.base_fee, gas_used),
+        transactions_root: tx_root::compute_root(&transaction_hashes),
+        receipts_root: receipt::receipts_root(&receipts),
+        outcomes_root: receipt::outcomes_root(&receipts),
+    })
+}
+
+/// Outcome of a batch. The guest commits it ABI-encoded, see `public_values`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransitionProof {
+    pub chain_id: u64,
+    pub old_state_root: B256,
+    pub new_state_root: B256,
+    pub batch_index: u64,
+    pub transaction_count: u64,
+    pub gas_used: u64,
+    /// Base fee the batch executed at.
+    pub base_fee: u64,
+    /// Base fee the following batch must execute at.
+    pub next_base_fee: u64,
+    /// `tx_root::compute_root` over the `hash_transaction` of every transaction.
+    pub transactions_root: B256,
+    /// `receipt::receipts_root`, the Ethereum receipts trie over the receipt of
+    /// every transaction.
+    pub receipts_root: B256,
+    /// `receipt::outcomes_root`, the status and error code of every transaction.
+    pub outcomes_root: B256,
+}
+
+impl AccountState {
+    fn payload_length(&self) -> usize {
+        self.nonce.length() + self.balance.length() + self.storage_root.length() + self.code_hash.length()
+    }
+}
+
+/// Ethereum account encoding, `[nonce, balance, storage_root, code_hash]`. The
+/// address is the key the account is stored under rather than part of it.
+impl Encodable for AccountState {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.storage_root.encode(out);
+        self.code_hash.encode(out);
+    }
+    
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+/// Inverse of the account encoding. The address decodes as zero and has to be
+/// filled in from wherever the account was looked up.
+impl Decodable for AccountState {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString);
+        }
+        if buf.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        
+        let (mut payload, rest) = buf.split_at(header.payload_length);
+        let account = Self {
+            address: Address::ZERO,
+            nonce: Decodable::decode(&mut payload)?,
+            balance: Decodable::decode(&mut payload)?,
+            storage_root: Decodable::decode(&mut payload)?,
+            code_hash: Decodable::decode(&mut payload)?,
+        };
+        if !payload.is_empty() {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: header.payload_length - payload.len(),
+            });
+        }
+        
+        *buf = rest;
+        Ok(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Bytes, Signature};
+    use k256::ecdsa::SigningKey;
+    use transaction::{TxEip1559, TypedTransaction};
+
+    fn account(address: Address, balance: u64) -> AccountState {
+        AccountState { balance: U256::from(balance), ..AccountState::new(address) }
+    }
+
+    fn eip1559(to: Address, value: u64, nonce: u64) -> TxEip1559 {
+        TxEip1559 {
+            chain_id: 1,
+            nonce,
+            max_priority_fee_per_gas: 1,
+            max_fee_per_gas: 1,
+            gas_limit: 21_000,
+            to: TxKind::Call(to),
+            value: U256::from(value),
+            input: Bytes::new(),
+            access_list: Vec::new(),
+        }
+    }
+
+    fn transfer(key: &SigningKey, to: Address, value: u64, nonce: u64) -> Transaction {
+        Transaction::sign(key, eip1559(to, value, nonce))
+    }
+
+    pub(crate) fn coinbase() -> Address {
+        Address::repeat_byte(0xfe)
+    }
+
+    pub(crate) fn env() -> BatchEnv {
+        BatchEnv { chain_id: 1, fee_recipient: coinbase(), base_fee: 0 }
+    }
+
+    /// Key of the funded sender in the test fixtures.
+    pub(crate) fn sender_key() -> SigningKey {
+        SigningKey::from_slice(&[0x11; 32]).unwrap()
+    }
+
+    fn execute(tx: &Transaction, accounts: &mut Vec<AccountState>) -> Result<u64, TransactionError> {
+        match execute_transaction(tx, accounts, &env(), &mut ContractState::default()) {
+            Ok(receipt) => Ok(receipt.gas_used),
+            Err(ExecutionError::Transaction(error)) => Err(error),
+            Err(ExecutionError::State(error)) => panic!("{error}"),
+        }
+    }
+
+    fn setup() -> (SigningKey, Vec<AccountState>) {
+        let key = sender_key();
+        let accounts = vec![
+            account(Address::from_private_key(&key), 1_000_000),
+            account(Address::repeat_byte(0xbb), 0),
+        ];
+        (key, accounts)
+    }
+
+    #[test]
+    fn account_encoding_matches_ethereum() {
+        let empty = AccountState::new(Address::ZERO);
+        let funded = AccountState {
+            nonce: 1,
+            balance: U256::from(1_000_000_000_000_000_000u64),
+            ..AccountState::new(Address::ZERO)
+        };
+        
+        let vectors = [
+            (&empty, "f8448080a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+            (&funded, "f84c01880de0b6b3a7640000a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+        ];
+        
+        for (account, expected) in vectors {
+            let encoded = alloy_rlp::encode(account);
+            assert_eq!(hex::encode(&encoded), expected);
+            assert_eq!(encoded.len(), account.length());
+            
+            let decoded = AccountState::decode(&mut encoded.as_slice()).unwrap();
+            assert_eq!(decoded.nonce, account.nonce);
+            assert_eq!(decoded.balance, account.balance);
+            assert_eq!(decoded.storage_root, account.storage_root);
+            assert_eq!(decoded.code_hash, account.code_hash);
+        }
+    }
+
+    #[test]
+    fn sequential_nonces_are_accepted() {
+        let (key, mut accounts) = setup();
+        let to = accounts[1].address;
+
+        for nonce in 0..3 {
+            execute(&transfer(&key, to, 100, nonce), &mut accounts).unwrap();
+        }
+
+        assert_eq!(accounts[0].nonce, 3);
+        assert_eq!(accounts[1].balance, U256::from(300));
+    }
+
+    #[test]
+    fn replay_within_batch_is_rejected() {
+        let (key, mut accounts) = setup();
+        let tx = transfer(&key, accounts[1].address, 100, 0);
+
+        execute(&tx, &mut accounts).unwrap();
+        let after_first = accounts.clone();
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::NonceTooLow { expected: 1, actual: 0 }));
+        assert_eq!(accounts[0].balance, after_first[0].balance);
+        assert_eq!(accounts[1].balance, after_first[1].balance);
+        assert_eq!(accounts[0].nonce, 1);
+    }
+
+    #[test]
+    fn replay_across_batches_is_rejected() {
+        let (key, mut accounts) = setup();
+        let batch = vec![
+            transfer(&key, accounts[1].address, 100, 0),
+            transfer(&key, accounts[1].address, 100, 1),
+        ];
+
+        for tx in &batch {
+            execute(tx, &mut accounts).unwrap();
+        }
+
+        // The post-state of one batch is the pre-state of the next.
+        for tx in &batch {
+            assert_eq!(execute(tx, &mut accounts), Err(TransactionError::NonceTooLow { expected: 2, actual: tx.nonce() }));
+        }
+        assert_eq!(accounts[1].balance, U256::from(200));
+    }
+
+    #[test]
+    fn call_to_precompile_runs_in_evm() {
+        let (key, mut accounts) = setup();
+        let ecrecover = Address::with_last_byte(1);
+        let tx = Transaction::sign(&key, TxEip1559 { gas_limit: 30_000, ..eip1559(ecrecover, 0, 0) });
+        let env = env();
+        let mut contracts = ContractState::default();
+        contracts.cover(ecrecover, None).unwrap();
+        contracts.cover(coinbase(), None).unwrap();
+
+        let receipt = execute_transaction(&tx, &mut accounts, &env, &mut contracts).unwrap();
+
+        // Intrinsic gas plus the 3000 ecrecover charges, even for empty input.
+        assert_eq!(receipt, TransactionReceipt::success(24_000));
+        assert_eq!(accounts[0].balance, U256::from(1_000_000 - 24_000));
+        assert_eq!(accounts[0].nonce, 1);
+    }
+
+    #[test]
+    fn transfer_to_unseen_recipient_creates_account() {
+        let (key, mut accounts) = setup();
+        let to = Address::repeat_byte(0xcc);
+
+        execute(&transfer(&key, to, 100, 0), &mut accounts).unwrap();
+
+        let created = accounts.iter().find(|a| a.address == to).unwrap();
+        assert_eq!(created.balance, U256::from(100));
+        assert_eq!(created.nonce, 0);
+        assert_eq!(created.code_hash, KECCAK_EMPTY);
+        assert_eq!(created.storage_root, EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn fees_are_paid_to_fee_recipient() {
+        let (key, mut accounts) = setup();
+        let supply_before: U256 = accounts.iter().map(|a| a.balance).sum();
+
+        let gas_used = execute(&transfer(&key, accounts[1].address, 100, 0), &mut accounts).unwrap();
+
+        let fee_recipient = accounts.iter().find(|a| a.address == coinbase()).unwrap();
+        assert_eq!(fee_recipient.balance, U256::from(gas_used));
+        assert_eq!(accounts[0].balance, U256::from(1_000_000 - 100 - gas_used));
+        assert_eq!(accounts.iter().map(|a| a.balance).sum::<U256>(), supply_before);
+    }
+
+    #[test]
+    fn base_fee_is_burned_and_tip_is_paid() {
+        let (key, mut accounts) = setup();
+        let tx = TxEip1559 {
+            gas_limit: 50_000,
+            max_fee_per_gas: 10,
+            max_priority_fee_per_gas: 2,
+            ..eip1559(accounts[1].address, 100, 0)
+        };
+        let tx = Transaction::sign(&key, tx);
+        let env = BatchEnv { base_fee: 7, ..env() };
+
+        let gas_used = execute_transaction(&tx, &mut accounts, &env, &mut ContractState::default()).unwrap().gas_used;
+
+        let fee_recipient = accounts.iter().find(|a| a.address == coinbase()).unwrap();
+        assert_eq!(fee_recipient.balance, U256::from(gas_used * 2));
+        assert_eq!(accounts[0].balance, U256::from(1_000_000 - 100 - gas_used * 9));
+    }
+
+    #[test]
+    fn max_fee_below_base_fee_is_rejected() {
+        let (key, mut accounts) = setup();
+        let tx = transfer(&key, accounts[1].address, 100, 0);
+        let env = BatchEnv { base_fee: 2, ..env() };
+
+        assert_eq!(
+            execute_transaction(&tx, &mut accounts, &env, &mut ContractState::default()),
+            Err(ExecutionError::Transaction(TransactionError::MaxFeeBelowBaseFee { max_fee_per_gas: 1, base_fee: 2 }))
+        );
+    }
+
+    #[test]
+    fn base_fee_tracks_gas_target() {
+        let gas_target = BATCH_GAS_LIMIT / ELASTICITY_MULTIPLIER;
+
+        assert_eq!(next_base_fee(1_000, gas_target), 1_000);
+        assert_eq!(next_base_fee(1_000, BATCH_GAS_LIMIT), 1_125);
+        assert_eq!(next_base_fee(1_000, 0), 875);
+        assert_eq!(next_base_fee(1, gas_target + 1), 2);
+    }
+
+    #[test]
+    fn claimed_sender_must_match_signer() {
+        let (key, mut accounts) = setup();
+        let mut tx = transfer(&key, accounts[1].address, 100, 0);
+        tx.from = accounts[1].address;
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::SignerMismatch { claimed: accounts[1].address, recovered: accounts[0].address }));
+    }
+
+    #[test]
+    fn signer_is_recovered_from_signature() {
+        let (key, accounts) = setup();
+        let tx = transfer(&key, accounts[1].address, 100, 0);
+
+        assert_eq!(tx.recover_signer(), Ok(accounts[0].address));
+    }
+
+    #[test]
+    fn tampered_transaction_recovers_another_signer() {
+        let (key, mut accounts) = setup();
+        let mut tx = transfer(&key, accounts[1].address, 100, 0);
+        if let TypedTransaction::Eip1559(inner) = &mut tx.tx {
+            inner.value = U256::from(900);
+        }
+
+        let recovered = tx.recover_signer().unwrap();
+        assert_ne!(recovered, accounts[0].address);
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::SignerMismatch { claimed: accounts[0].address, recovered }));
+        assert_eq!(accounts[0].nonce, 0);
+        assert_eq!(accounts[1].balance, U256::ZERO);
+    }
+
+    #[test]
+    fn unrecoverable_signature_is_rejected() {
+        let (key, mut accounts) = setup();
+        let mut tx = transfer(&key, accounts[1].address, 100, 0);
+        // No point on secp256k1 has x = 5, so no public key can be recovered.
+        tx.signature = Signature::from_rs_and_parity(U256::from(5), U256::from(1), 0u64).unwrap();
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::InvalidSignature));
+        assert_eq!(accounts[0].nonce, 0);
+    }
+
+    #[test]
+    fn transaction_for_another_chain_is_rejected() {
+        let (key, mut accounts) = setup();
+        let tx = Transaction::sign(&key, TxEip1559 { chain_id: 5, ..eip1559(accounts[1].address, 100, 0) });
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::ChainIdMismatch { expected: 1, actual: Some(5) }));
+        assert_eq!(accounts[0].nonce, 0);
+    }
+
+    #[test]
+    fn future_nonce_is_rejected() {
+        let (key, mut accounts) = setup();
+        let tx = transfer(&key, accounts[1].address, 100, 1);
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::NonceTooHigh { expected: 0, actual: 1 }));
+        assert_eq!(accounts[0].nonce, 0);
+        assert_eq!(accounts[1].balance, U256::ZERO);
+    }
+
+    #[test]
+    fn transfer_exceeding_balance_fails_but_pays_gas() {
+        let (key, mut accounts) = setup();
+        accounts[0].balance = U256::from(30_000);
+        let tx = transfer(&key, accounts[1].address, 10_000, 0);
+        let env = env();
+
+        let receipt = execute_transaction(&tx, &mut accounts, &env, &mut ContractState::default()).unwrap();
+
+        let error = TransactionError::InsufficientBalanceForValue { balance: U256::from(9_000), value: U256::from(10_000) };
+        assert_eq!(receipt, TransactionReceipt::failed(error, 21_000));
+        assert_eq!(accounts[0].nonce, 1);
+        assert_eq!(accounts[0].balance, U256::from(9_000));
+        assert_eq!(accounts[1].balance, U256::ZERO);
+        let fee_recipient = accounts.iter().find(|a| a.address == coinbase()).unwrap();
+        assert_eq!(fee_recipient.balance, U256::from(21_000));
+    }
+
+    #[test]
+    fn sender_unable_to_prepay_gas_is_rejected() {
+        let (key, mut accounts) = setup();
+        accounts[0].balance = U256::from(20_999);
+        let tx = transfer(&key, accounts[1].address, 0, 0);
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::InsufficientFundsForGas { balance: U256::from(20_999), max_gas_cost: U256::from(21_000) }));
+        assert_eq!(accounts[0].nonce, 0);
+        assert_eq!(accounts[0].balance, U256::from(20_999));
+    }
+
+    #[test]
+    fn nonce_at_maximum_is_rejected() {
+        let (key, mut accounts) = setup();
+        accounts[0].nonce = u64::MAX;
+        let before = accounts.clone();
+        let tx = transfer(&key, accounts[1].address, 100, u64::MAX);
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::NonceOverflow { nonce: u64::MAX }));
+        assert_eq!(accounts, before);
+    }
+
+    #[test]
+    fn credit_past_maximum_balance_is_rejected_without_effects() {
+        let (key, mut accounts) = setup();
+        accounts[1].balance = U256::MAX;
+        let before = accounts.clone();
+        let tx = transfer(&key, accounts[1].address, 100, 0);
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::BalanceOverflow { address: accounts[1].address }));
+        assert_eq!(accounts, before);
+    }
+
+    #[test]
+    fn fee_recipient_overflow_is_rejected_without_effects() {
+        let (key, mut accounts) = setup();
+        accounts.push(AccountState { balance: U256::MAX, ..AccountState::new(coinbase()) });
+        let before = accounts.clone();
+        let tx = transfer(&key, accounts[1].address, 100, 0);
+
+        assert_eq!(execute(&tx, &mut accounts), Err(TransactionError::BalanceOverflow { address: coinbase() }));
+        assert_eq!(accounts, before);
+    }
+
+    #[test]
+    fn intrinsic_gas_counts_access_list() {
+        let (key, accounts) = setup();
+        let item = transaction::AccessListItem { address: Address::ZERO, storage_keys: vec![B256::ZERO; 2] };
+        let tx = Transaction::sign(&key, TxEip1559 { access_list: vec![item], ..eip1559(accounts[1].address, 0, 0) });
+
+        assert_eq!(
+            intrinsic_gas(&tx),
+            Some(TX_BASE_GAS + TX_ACCESS_LIST_ADDRESS_GAS + 2 * TX_ACCESS_LIST_STORAGE_KEY_GAS)
+        );
+    }
+
+    #[test]
+    fn intrinsic_gas_counts_contract_creation() {
+        let (key, _) = setup();
+        let init_code = Bytes::from(vec![0x01; 33]);
+        let tx = Transaction::sign(&key, TxEip1559 { to: TxKind::Create, input: init_code, ..eip1559(Address::ZERO, 0, 0) });
+
+        assert_eq!(
+            intrinsic_gas(&tx),
+            Some(TX_BASE_GAS + TX_CREATE_GAS + 2 * INITCODE_WORD_GAS + 33 * TX_DATA_NON_ZERO_GAS)
+        );
+    }
+
+    #[test]
+    fn creation_unable_to_pay_value_fails_but_pays_gas() {
+        let (key, mut accounts) = setup();
+        let balance = accounts[0].balance;
+        let tx = Transaction::sign(&key, TxEip1559 { to: TxKind::Create, gas_limit: 53_000, ..eip1559(Address::ZERO, u64::MAX, 0) });
+
+        assert_eq!(execute(&tx, &mut accounts), Ok(53_000));
+        assert_eq!(accounts[0].balance, balance - U256::from(53_000));
+        assert_eq!(accounts[0].nonce, 1);
+        assert!(accounts.iter().all(|a| Some(a.address) != tx.create_address()));
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+        /// Every wei is either still held by an account or burned as base fee,
+        /// and rejected transactions leave no trace.
+        #[test]
+        fn total_supply_is_conserved(
+            balances in proptest::collection::vec(proptest::num::u128::ANY, 3),
+            base_fee in 0u64..100,
+            txs in proptest::collection::vec(
+                (0usize..3, 0usize..4, proptest::num::u128::ANY, 0u8..4, 20_000u64..60_000, 0u64..200, 0u64..200),
+                0..8,
+            ),
+        ) {
+            let keys: Vec<SigningKey> = (1..=3u8).map(|byte| SigningKey::from_slice(&[byte; 32]).unwrap()).collect();
+            let mut accounts: Vec<AccountState> = keys
+                .iter()
+                .zip(&balances)
+                .map(|(key, balance)| AccountState { balance: U256::from(*balance), ..AccountState::new(Address::from_private_key(key)) })
+                .collect();
+            let env = BatchEnv { base_fee, ..env() };
+            let supply = |accounts: &[AccountState]| accounts.iter().map(|a| a.balance).sum::<U256>();
+            let initial_supply = supply(&accounts);
+            let mut burned = U256::ZERO;
+
+            for (sender, recipient, value, nonce_skew, gas_limit, max_fee_per_gas, max_priority_fee_per_gas) in txs {
+                let from = Address::from_private_key(&keys[sender]);
+                let to = match recipient {
+                    3 => Address::repeat_byte(0xcc),
+                    idx => Address::from_private_key(&keys[idx]),
+                };
+                let nonce = accounts.iter().find(|a| a.address == from).unwrap().nonce + u64::from(nonce_skew == 0);
+                let tx = Transaction::sign(&keys[sender], TxEip1559 {
+                    nonce,
+                    gas_limit,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    value: U256::from(value),
+                    ..eip1559(to, 0, 0)
+                });
+
+                let before = accounts.clone();
+                match execute_transaction(&tx, &mut accounts, &env, &mut ContractState::default()) {
+                    Ok(receipt) => burned += U256::from(receipt.gas_used) * U256::from(base_fee),
+                    Err(_) => proptest::prop_assert_eq!(&accounts, &before),
+                }
+                proptest::prop_assert_eq!(supply(&accounts) + burned, initial_supply);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn rejected_transaction_does_not_stop_batch() {
+        let (mut transition, _, mut state) = batch();
+        let (key, accounts) = setup();
+        transition.transactions.insert(1, transfer(&key, accounts[1].address, 100, 7));
+
+        let receipts = apply_batch(&transition, &mut state, &ContractWitness::default()).unwrap();
+
+        let rejected = TransactionReceipt::rejected(TransactionError::NonceTooHigh { expected: 1, actual: 7 });
+        assert_eq!(
+            receipts,
+            vec![
+                TransactionReceipt { tx_type: 2, cumulative_gas_used: 21_000, ..TransactionReceipt::success(21_000) },
+                TransactionReceipt { tx_type: 2, cumulative_gas_used: 21_000, ..rejected },
+                TransactionReceipt { tx_type: 2, cumulative_gas_used: 42_000, ..TransactionReceipt::success(21_000) },
+            ]
+        );
+        assert_eq!(state.get(&accounts[0].address).unwrap().unwrap().nonce, 2);
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    fn batch() -> (StateTransition, Vec<Bytes>, mpt::StateTrie) {
+        let (key, accounts) = setup();
+        let genesis = mpt::StateTrie::from_accounts(&accounts);
+        let transition = StateTransition {
+            chain_id: 1,
+            transactions: vec![transfer(&key, accounts[1].address, 100, 0), transfer(&key, Address::repeat_byte(0xcc), 5, 1)],
+            old_state_root: genesis.root(),
+            new_state_root: B256::ZERO,
+            batch_index: 0,
+            fee_recipient: coinbase(),
+            base_fee: 0,
+        };
+        let mut nodes: Vec<Bytes> = touched_addresses(&transition).iter().flat_map(|a| genesis.prove(a)).collect();
+        nodes.sort();
+        nodes.dedup();
+        (transition, nodes, genesis)
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn batch_over_witness_matches_full_state() {
+        let (transition, nodes, mut full) = batch();
+        let mut witness = mpt::StateTrie::open(transition.old_state_root, nodes).unwrap();
+
+        let receipts = apply_batch(&transition, &mut witness, &ContractWitness::default()).unwrap();
+        let cumulative_gas_used: Vec<u64> = receipts.iter().map(|receipt| receipt.cumulative_gas_used).collect();
+        assert_eq!(cumulative_gas_used, vec![21_000, 42_000]);
+        assert!(receipts.iter().all(|receipt| receipt.status == receipt::TransactionStatus::Success));
+
+        apply_batch(&transition, &mut full, &ContractWitness::default()).unwrap();
+        assert_eq!(witness.root(), full.root());
+        assert_ne!(witness.root(), transition.old_state_root);
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn touched_empty_accounts_are_not_created() {
+        let (key, accounts) = setup();
+        let genesis = mpt::StateTrie::from_accounts(&accounts);
+        let free = TxEip1559 { max_priority_fee_per_gas: 0, max_fee_per_gas: 0, ..eip1559(Address::repeat_byte(0xdd), 0, 0) };
+        let transition = StateTransition {
+            chain_id: 1,
+            transactions: vec![Transaction::sign(&key, free)],
+            old_state_root: genesis.root(),
+            new_state_root: B256::ZERO,
+            batch_index: 0,
+            fee_recipient: coinbase(),
+            base_fee: 0,
+        };
+        let mut state = genesis.clone();
+
+        let receipts = apply_batch(&transition, &mut state, &ContractWitness::default()).unwrap();
+
+        assert_eq!(receipts[0].status, receipt::TransactionStatus::Success);
+        assert_eq!(state.get(&Address::repeat_byte(0xdd)), Ok(None));
+        assert_eq!(state.get(&coinbase()), Ok(None));
+        let mut expected = accounts;
+        expected[0].nonce = 1;
+        assert_eq!(state.root(), mpt::StateTrie::from_accounts(&expected).root());
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn execute_batch_checks_the_claimed_root() {
+        let (mut transition, witness, mut full) = batch();
+        apply_batch(&transition, &mut full, &ContractWitness::default()).unwrap();
+        let input = input::GuestInput {
+            transition: transition.clone(),
+            witness: witness.clone(),
+            contracts: ContractWitness::default(),
+        };
+
+        assert_eq!(
+            execute_batch(input).unwrap_err(),
+            Error::StateRootMismatch { claimed: B256::ZERO, computed: full.root() }
+        );
+
+        transition.new_state_root = full.root();
+        let input = input::GuestInput { transition, witness, contracts: ContractWitness::default() };
+        let proof = execute_batch(input).unwrap();
+        assert_eq!(proof.new_state_root, full.root());
+        assert_eq!(proof.transaction_count, 2);
+        assert_eq!(proof.gas_used, 42_000);
+    }
+
+    #[cfg(not(feature = "smt-state"))]
+    #[test]
+    fn guest_input_binary_round_trip() {
+        let (transition, witness, _) = batch();
+        let input = input::GuestInput { transition, witness, contracts: ContractWitness::default() };
+
+        let binary = input.to_binary();
+        let decoded = input::GuestInput::from_binary(&binary).unwrap();
+        assert_eq!(decoded.to_binary(), binary);
+        assert_eq!(decoded.witness, input.witness);
+        assert_eq!(
+            decoded.transition.transactions.iter().map(hash_transaction).collect::<Vec<_>>(),
+            input.transition.transactions.iter().map(hash_transaction).collect::<Vec<_>>(),
+        );
+        assert!(binary.len() < input.to_json().len());
+    }
+}
@@ -0,0 +1,718 @@
+use alloc::collections::BTreeMap;
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::mem;
+
+use alloy_primitives::{b256, keccak256, Address, Bytes, B256};
+use alloy_rlp::{Decodable, Encodable, Header, EMPTY_STRING_CODE};
+
+use crate::error::StateError;
+use crate::state::StateWitness;
+use crate::AccountState;
+
+/// Root of a trie with no entries, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT_HASH: B256 =
+    b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// Code hash of an account without code, `keccak256("")`.
+pub const KECCAK_EMPTY: B256 =
+    b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+
+#[derive(Debug, Clone, Default)]
+enum Node {
+    #[default]
+    Empty,
+    Leaf {
+        path: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Extension {
+        path: Vec<u8>,
+        child: Box<Node>,
+    },
+    Branch {
+        children: Box<[Node; 16]>,
+        value: Option<Vec<u8>>,
+    },
+    /// Subtree known only by its hash because the witness did not include it.
+    Digest(B256),
+}
+
+/// Merkle Patricia Trie as specified in the Ethereum yellow paper.
+///
+/// A trie built natively from the full state and a trie opened from a witness
+/// share the same representation; the latter keeps unwitnessed subtrees as
+/// digests and refuses any operation that would need to look inside them.
+#[derive(Debug, Clone, Default)]
+pub struct MerklePatriciaTrie {
+    root: Node,
+}
+
+impl MerklePatriciaTrie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuilds the part of the trie under `root` covered by `nodes`, the RLP
+    /// encodings of trie nodes in any order (as returned by `eth_getProof`).
+    ///
+    /// Nodes are found by hash, but a node that does not encode canonically
+    /// would still change the root once re-encoded, so the rebuilt trie is
+    /// checked against `root`.
+    pub fn from_nodes(root: B256, nodes: &[Bytes]) -> Result<Self, StateError> {
+        if root == EMPTY_ROOT_HASH {
+            return Ok(Self::new());
+        }
+
+        let nodes: BTreeMap<B256, &[u8]> =
+            nodes.iter().map(|node| (keccak256(node), node.as_ref())).collect();
+        let trie = match nodes.get(&root) {
+            Some(raw) => Self { root: decode_node(raw, &nodes)? },
+            None => Self { root: Node::Digest(root) },
+        };
+
+        let computed = trie.hash();
+        if computed != root {
+            return Err(StateError::WitnessRootMismatch { claimed: root, computed });
+        }
+        Ok(trie)
+    }
+
+    pub fn hash(&self) -> B256 {
+        match &self.root {
+            Node::Empty => EMPTY_ROOT_HASH,
+            Node::Digest(hash) => *hash,
+            node => keccak256(encode_node(node)),
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Result<Option<&[u8]>, StateError> {
+        get(&self.root, &to_nibbles(key))
+    }
+
+    pub fn insert(&mut self, key: &[u8], value: Vec<u8>) -> Result<(), StateError> {
+        insert(&mut self.root, &to_nibbles(key), value)
+    }
+
+    /// Removes `key` if present, merging the nodes around it back into the
+    /// canonical shape so the hash matches a trie built without it.
+    pub fn remove(&mut self, key: &[u8]) -> Result<(), StateError> {
+        remove(&mut self.root, &to_nibbles(key))
+    }
+
+    /// Encoded nodes on the path to `key`, proving either its value or its absence.
+    pub fn prove(&self, key: &[u8]) -> Vec<Bytes> {
+        self.prove_path(key, false)
+    }
+
+    /// Like `prove`, plus every child of the branches on the path: removing
+    /// `key` may leave one of them alone in its branch, to be merged upwards.
+    pub fn prove_removal(&self, key: &[u8]) -> Vec<Bytes> {
+        self.prove_path(key, true)
+    }
+
+    /// Encodings of every node referenced by hash, from which `from_nodes`
+    /// rebuilds the whole trie.
+    pub fn nodes(&self) -> Vec<Bytes> {
+        let mut nodes = Vec::new();
+        if !matches!(self.root, Node::Empty | Node::Digest(_)) {
+            collect_nodes(&self.root, true, &mut nodes);
+        }
+        nodes
+    }
+
+    fn prove_path(&self, key: &[u8], siblings: bool) -> Vec<Bytes> {
+        let nibbles = to_nibbles(key);
+        let mut path = nibbles.as_slice();
+        let mut node = &self.root;
+        let mut proof = Vec::new();
+
+        if !matches!(node, Node::Empty | Node::Digest(_)) {
+            proof.push(Bytes::from(encode_node(node)));
+        }
+
+        loop {
+            node = match node {
+                Node::Branch { children, .. } => match path.split_first() {
+                    Some((&index, rest)) => {
+                        if siblings {
+                            proof.extend(
+                                children
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(i, child)| *i != index as usize && !matches!(child, Node::Digest(_)))
+                                    .map(|(_, child)| encode_node(child))
+                                    .filter(|encoded| encoded.len() >= 32)
+                                    .map(Bytes::from),
+                            );
+                        }
+                        path = rest;
+                        &children[index as usize]
+                    }
+                    None => break,
+                },
+                Node::Extension { path: prefix, child } if path.starts_with(prefix) => {
+                    path = &path[prefix.len()..];
+                    child
+                }
+                _ => break,
+            };
+
+            if matches!(node, Node::Empty | Node::Digest(_)) {
+                break;
+            }
+            let encoded = encode_node(node);
+            if encoded.len() >= 32 {
+                proof.push(Bytes::from(encoded));
+            }
+        }
+
+        proof
+    }
+}
+
+/// World state trie mapping `keccak256(address)` to the RLP account
+/// `[nonce, balance, storage_root, code_hash]`.
+#[derive(Debug, Clone, Default)]
+pub struct StateTrie {
+    trie: MerklePatriciaTrie,
+}
+
+impl StateTrie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_accounts(accounts: &[AccountState]) -> Self {
+        let mut state = Self::new();
+        for account in accounts {
+            state.update(account).expect("a fully built trie has no missing nodes");
+        }
+        state
+    }
+
+    pub fn prove(&self, address: &Address) -> Vec<Bytes> {
+        self.trie.prove(keccak256(address).as_slice())
+    }
+
+    /// `prove`, for an account the batch deletes.
+    pub fn prove_removal(&self, address: &Address) -> Vec<Bytes> {
+        self.trie.prove_removal(keccak256(address).as_slice())
+    }
+}
+
+impl StateWitness for StateTrie {
+    type Proof = Vec<Bytes>;
+
+    fn open(root: B256, proof: Self::Proof) -> Result<Self, StateError> {
+        Ok(Self { trie: MerklePatriciaTrie::from_nodes(root, &proof)? })
+    }
+
+    fn root(&self) -> B256 {
+        self.trie.hash()
+    }
+
+    fn get(&self, address: &Address) -> Result<Option<AccountState>, StateError> {
+        self.trie
+            .get(keccak256(address).as_slice())?
+            .map(|mut encoded| {
+                let account = AccountState::decode(&mut encoded).map_err(|_| StateError::InvalidAccountEncoding)?;
+                Ok(AccountState { address: *address, ..account })
+            })
+            .transpose()
+    }
+
+    fn update(&mut self, account: &AccountState) -> Result<(), StateError> {
+        self.trie.insert(keccak256(account.address).as_slice(), alloy_rlp::encode(account))
+    }
+
+    fn remove(&mut self, address: &Address) -> Result<(), StateError> {
+        self.trie.remove(keccak256(address).as_slice())
+    }
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+fn get<'a>(node: &'a Node, path: &[u8]) -> Result<Option<&'a [u8]>, StateError> {
+    match node {
+        Node::Empty => Ok(None),
+        Node::Digest(_) => Err(StateError::MissingTrieNode),
+        Node::Leaf { path: key, value } => Ok((key.as_slice() == path).then_some(value.as_slice())),
+        Node::Extension { path: prefix, child } => match path.strip_prefix(prefix.as_slice()) {
+            Some(rest) => get(child, rest),
+            None => Ok(None),
+        },
+        Node::Branch { children, value } => match path.split_first() {
+            Some((&index, rest)) => get(&children[index as usize], rest),
+            None => Ok(value.as_deref()),
+        },
+    }
+}
+
+fn insert(node: &mut Node, path: &[u8], value: Vec<u8>) -> Result<(), StateError> {
+    match node {
+        Node::Digest(_) => return Err(StateError::MissingTrieNode),
+        Node::Branch { children, value: slot } => {
+            return match path.split_first() {
+                Some((&index, rest)) => insert(&mut children[index as usize], rest, value),
+                None => {
+                    *slot = Some(value);
+                    Ok(())
+                }
+            };
+        }
+        Node::Extension { path: prefix, child } if path.starts_with(prefix) => {
+            let rest = &path[prefix.len()..];
+            return insert(child, rest, value);
+        }
+        Node::Leaf { path: key, value: slot } if key.as_slice() == path => {
+            *slot = value;
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Everything left restructures the node without descending into unknown
+    // subtrees, so it cannot fail.
+    *node = match mem::take(node) {
+        Node::Empty => Node::Leaf { path: path.to_vec(), value },
+        Node::Leaf { path: key, value: existing } => {
+            let common = common_prefix(&key, path);
+            let mut children = Box::<[Node; 16]>::default();
+            let mut branch_value = None;
+            place(&mut children, &mut branch_value, &key[common..], existing);
+            place(&mut children, &mut branch_value, &path[common..], value);
+            with_prefix(&path[..common], Node::Branch { children, value: branch_value })
+        }
+        Node::Extension { path: prefix, child } => {
+            let common = common_prefix(&prefix, path);
+            let mut children = Box::<[Node; 16]>::default();
+            let mut branch_value = None;
+            children[prefix[common] as usize] = with_prefix(&prefix[common + 1..], *child);
+            place(&mut children, &mut branch_value, &path[common..], value);
+            with_prefix(&path[..common], Node::Branch { children, value: branch_value })
+        }
+        Node::Branch { .. } | Node::Digest(_) => unreachable!("handled above"),
+    };
+
+    Ok(())
+}
+
+fn remove(node: &mut Node, path: &[u8]) -> Result<(), StateError> {
+    match node {
+        Node::Empty => return Ok(()),
+        Node::Digest(_) => return Err(StateError::MissingTrieNode),
+        Node::Leaf { path: key, .. } => {
+            if key.as_slice() == path {
+                *node = Node::Empty;
+            }
+            return Ok(());
+        }
+        Node::Extension { path: prefix, child } => match path.strip_prefix(prefix.as_slice()) {
+            Some(rest) => remove(child, rest)?,
+            None => return Ok(()),
+        },
+        Node::Branch { children, value } => match path.split_first() {
+            Some((&index, rest)) => remove(&mut children[index as usize], rest)?,
+            None => *value = None,
+        },
+    }
+
+    *node = collapse(mem::take(node))?;
+    Ok(())
+}
+
+/// Restores what a removal below `node` may have broken: an extension always
+/// leads to a branch, and a branch holds at least two entries.
+fn collapse(node: Node) -> Result<Node, StateError> {
+    match node {
+        Node::Extension { path, child } => Ok(merge(&path, *child)),
+        Node::Branch { mut children, value } => {
+            let occupied: Vec<usize> = (0..16).filter(|&i| !matches!(children[i], Node::Empty)).collect();
+            match (occupied.as_slice(), value) {
+                ([], None) => Ok(Node::Empty),
+                ([], Some(value)) => Ok(Node::Leaf { path: Vec::new(), value }),
+                // A lone child known only by its digest may be a leaf or an
+                // extension that has to absorb the branch nibble.
+                (&[index], None) => match mem::take(&mut children[index]) {
+                    Node::Digest(_) => Err(StateError::MissingTrieNode),
+                    child => Ok(merge(&[index as u8], child)),
+                },
+                (_, value) => Ok(Node::Branch { children, value }),
+            }
+        }
+        node => Ok(node),
+    }
+}
+
+/// `node` moved down by `prefix`, folding the prefix into its own path where
+/// it has one.
+fn merge(prefix: &[u8], node: Node) -> Node {
+    match node {
+        Node::Empty => Node::Empty,
+        Node::Leaf { path, value } => Node::Leaf { path: [prefix, &path].concat(), value },
+        Node::Extension { path, child } => Node::Extension { path: [prefix, &path].concat(), child },
+        node => with_prefix(prefix, node),
+    }
+}
+
+fn place(children: &mut [Node; 16], branch_value: &mut Option<Vec<u8>>, path: &[u8], value: Vec<u8>) {
+    match path.split_first() {
+        Some((&index, rest)) => children[index as usize] = Node::Leaf { path: rest.to_vec(), value },
+        None => *branch_value = Some(value),
+    }
+}
+
+fn with_prefix(prefix: &[u8], node: Node) -> Node {
+    if prefix.is_empty() {
+        node
+    } else {
+        Node::Extension { path: prefix.to_vec(), child: Box::new(node) }
+    }
+}
+
+fn collect_nodes(node: &Node, root: bool, nodes: &mut Vec<Bytes>) {
+    let encoded = match node {
+        Node::Empty | Node::Digest(_) => return,
+        node => encode_node(node),
+    };
+    if root || encoded.len() >= 32 {
+        nodes.push(Bytes::from(encoded));
+    }
+    match node {
+        Node::Extension { child, .. } => collect_nodes(child, false, nodes),
+        Node::Branch { children, .. } => {
+            for child in children.iter() {
+                collect_nodes(child, false, nodes);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Hex-prefix encoding of a nibble path, flagging whether it terminates in a leaf.
+fn encode_path(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 0x20 } else { 0x00 };
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), StateError> {
+    let (&first, rest) = encoded.split_first().ok_or(StateError::InvalidTrieNode)?;
+    let leaf = match first >> 4 {
+        0 | 1 => false,
+        2 | 3 => true,
+        _ => return Err(StateError::InvalidTrieNode),
+    };
+
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Ok((nibbles, leaf))
+}
+
+fn encode_node(node: &Node) -> Vec<u8> {
+    let mut payload = Vec::new();
+    match node {
+        Node::Empty => return vec![EMPTY_STRING_CODE],
+        Node::Digest(_) => unreachable!("digests are only ever encoded as references"),
+        Node::Leaf { path, value } => {
+            encode_path(path, true).as_slice().encode(&mut payload);
+            value.as_slice().encode(&mut payload);
+        }
+        Node::Extension { path, child } => {
+            encode_path(path, false).as_slice().encode(&mut payload);
+            encode_reference(child, &mut payload);
+        }
+        Node::Branch { children, value } => {
+            for child in children.iter() {
+                encode_reference(child, &mut payload);
+            }
+            value.as_deref().unwrap_or_default().encode(&mut payload);
+        }
+    }
+
+    let mut out = Vec::with_capacity(payload.len() + 3);
+    Header { list: true, payload_length: payload.len() }.encode(&mut out);
+    out.extend_from_slice(&payload);
+    out
+}
+
+/// Children shorter than a hash are embedded in their parent; everything else
+/// is referenced by the hash of its encoding.
+fn encode_reference(node: &Node, out: &mut Vec<u8>) {
+    match node {
+        Node::Empty => out.push(EMPTY_STRING_CODE),
+        Node::Digest(hash) => hash.as_slice().encode(out),
+        node => {
+            let encoded = encode_node(node);
+            if encoded.len() < 32 {
+                out.extend_from_slice(&encoded);
+            } else {
+                keccak256(&encoded).as_slice().encode(out);
+            }
+        }
+    }
+}
+
+fn decode_node(raw: &[u8], nodes: &BTreeMap<B256, &[u8]>) -> Result<Node, StateError> {
+    let items = list_items(raw)?;
+    match items.len() {
+        2 => {
+            let (path, leaf) = decode_path(string_payload(items[0])?)?;
+            if leaf {
+                Ok(Node::Leaf { path, value: string_payload(items[1])?.to_vec() })
+            } else {
+                Ok(Node::Extension { path, child: Box::new(decode_reference(items[1], nodes)?) })
+            }
+        }
+        17 => {
+            let mut children = Box::<[Node; 16]>::default();
+            for (child, item) in children.iter_mut().zip(&items) {
+                *child = decode_reference(item, nodes)?;
+            }
+            let value = string_payload(items[16])?;
+            let value = (!value.is_empty()).then(|| value.to_vec());
+            Ok(Node::Branch { children, value })
+        }
+        _ => Err(StateError::InvalidTrieNode),
+    }
+}
+
+fn decode_reference(item: &[u8], nodes: &BTreeMap<B256, &[u8]>) -> Result<Node, StateError> {
+    let header = Header::decode(&mut &item[..]).map_err(|_| StateError::InvalidTrieNode)?;
+    if header.list {
+        return decode_node(item, nodes);
+    }
+
+    let payload = string_payload(item)?;
+    match payload.len() {
+        0 => Ok(Node::Empty),
+        32 => {
+            let hash = B256::from_slice(payload);
+            match nodes.get(&hash) {
+                Some(raw) => decode_node(raw, nodes),
+                None => Ok(Node::Digest(hash)),
+            }
+        }
+        _ => Err(StateError::InvalidTrieNode),
+    }
+}
+
+/// Splits an RLP list into the raw encodings of its items.
+fn list_items(raw: &[u8]) -> Result<Vec<&[u8]>, StateError> {
+    let mut buf = raw;
+    let header = Header::decode(&mut buf).map_err(|_| StateError::InvalidTrieNode)?;
+    if !header.list || header.payload_length != buf.len() {
+        return Err(StateError::InvalidTrieNode);
+    }
+
+    let mut items = Vec::new();
+    while !buf.is_empty() {
+        let start = buf;
+        let item = Header::decode(&mut buf).map_err(|_| StateError::InvalidTrieNode)?;
+        let length = start.len() - buf.len() + item.payload_length;
+        if length > start.len() {
+            return Err(StateError::InvalidTrieNode);
+        }
+        items.push(&start[..length]);
+        buf = &start[length..];
+    }
+    Ok(items)
+}
+
+fn string_payload(item: &[u8]) -> Result<&[u8], StateError> {
+    let mut buf = item;
+    let header = Header::decode(&mut buf).map_err(|_| StateError::InvalidTrieNode)?;
+    if header.list || header.payload_length > buf.len() {
+        return Err(StateError::InvalidTrieNode);
+    }
+    Ok(&buf[..header.payload_length])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    type Entries<'a> = &'a [(&'a [u8], &'a [u8])];
+
+    fn trie(entries: Entries) -> MerklePatriciaTrie {
+        let mut trie = MerklePatriciaTrie::new();
+        for (key, value) in entries {
+            trie.insert(key, value.to_vec()).unwrap();
+        }
+        trie
+    }
+
+    const PUPPY: [(&[u8], &[u8]); 4] = [(b"do", b"verb"), (b"horse", b"stallion"), (b"doge", b"coin"), (b"dog", b"puppy")];
+
+    /// Vectors from `TrieTests/trieanyorder.json` in `ethereum/tests`.
+    #[test]
+    fn trie_root_vectors() {
+        let vectors: [(Entries, B256); 4] = [
+            (
+                &[(b"doe", b"reindeer"), (b"dog", b"puppy"), (b"dogglesworth", b"cat")],
+                b256!("8aad789dff2f538bca5d8ea56e8abe10f4c7ba3a5dea95fea4cd6e7c3a1168d3"),
+            ),
+            (&PUPPY, b256!("5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")),
+            (&[(b"foo", b"bar"), (b"food", b"bass")], b256!("17beaa1648bafa633cda809c90c04af50fc8aed3cb40d16efbddee6fdf63c4c3")),
+            (
+                &[(b"be", b"e"), (b"dog", b"puppy"), (b"bed", b"d")],
+                b256!("3f67c7a47520f79faa29255d2d3c084a7a6df0453116ed7232ff10277a8be68b"),
+            ),
+        ];
+
+        assert_eq!(MerklePatriciaTrie::new().hash(), EMPTY_ROOT_HASH);
+        for (entries, root) in vectors {
+            assert_eq!(trie(entries).hash(), root);
+            let reversed: Vec<_> = entries.iter().rev().copied().collect();
+            assert_eq!(trie(&reversed).hash(), root);
+        }
+    }
+
+    /// `emptyValues` from `TrieTests/trietest.json`, where an empty value removes the key.
+    #[test]
+    fn removal_vector() {
+        let steps: [(&[u8], &[u8]); 8] = [
+            (b"do", b"verb"),
+            (b"ether", b"wookiedoo"),
+            (b"horse", b"stallion"),
+            (b"shaman", b"horse"),
+            (b"doge", b"coin"),
+            (b"ether", b""),
+            (b"dog", b"puppy"),
+            (b"shaman", b""),
+        ];
+        let mut trie = MerklePatriciaTrie::new();
+        for (key, value) in steps {
+            match value {
+                [] => trie.remove(key).unwrap(),
+                value => trie.insert(key, value.to_vec()).unwrap(),
+            }
+        }
+
+        assert_eq!(trie.hash(), b256!("5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84"));
+    }
+
+    #[test]
+    fn removal_collapses_to_canonical_shape() {
+        // Branch value left alone, lone leaf merged into an extension, branch
+        // merged into an extension, absent key.
+        let cases: [(Entries, &[u8]); 5] = [
+            (&[(b"do", b"verb"), (b"dog", b"puppy")], b"dog"),
+            (&[(b"do", b"verb"), (b"dog", b"puppy")], b"do"),
+            (&[(b"doe", b"reindeer"), (b"dog", b"puppy"), (b"dogglesworth", b"cat")], b"doe"),
+            (&[(b"doe", b"reindeer"), (b"dog", b"puppy"), (b"dogglesworth", b"cat")], b"dog"),
+            (&PUPPY, b"cat"),
+        ];
+
+        for (entries, removed) in cases {
+            let mut full = trie(entries);
+            full.remove(removed).unwrap();
+            let rest: Vec<_> = entries.iter().filter(|(key, _)| *key != removed).copied().collect();
+            assert_eq!(full.hash(), trie(&rest).hash());
+        }
+
+        let mut emptied = trie(&PUPPY);
+        for (key, _) in PUPPY {
+            emptied.remove(key).unwrap();
+        }
+        assert_eq!(emptied.hash(), EMPTY_ROOT_HASH);
+    }
+
+    fn accounts() -> Vec<AccountState> {
+        (1..=20u8)
+            .map(|byte| AccountState { balance: U256::from(byte), ..AccountState::new(Address::repeat_byte(byte)) })
+            .collect()
+    }
+
+    #[test]
+    fn witness_reads_and_writes_like_the_full_trie() {
+        let mut full = StateTrie::from_accounts(&accounts());
+        let present = Address::repeat_byte(3);
+        let absent = Address::repeat_byte(0xaa);
+        let proof = [full.prove(&present), full.prove(&absent)].concat();
+        let mut witness = StateTrie::open(full.root(), proof).unwrap();
+
+        assert_eq!(witness.get(&present), full.get(&present));
+        assert_eq!(witness.get(&absent), Ok(None));
+        assert_eq!(witness.get(&Address::repeat_byte(4)), Err(StateError::MissingTrieNode));
+
+        let updated = AccountState { nonce: 1, ..AccountState::new(present) };
+        let created = AccountState { balance: U256::from(7), ..AccountState::new(absent) };
+        for state in [&mut full, &mut witness] {
+            state.update(&updated).unwrap();
+            state.update(&created).unwrap();
+        }
+        assert_eq!(witness.root(), full.root());
+        assert_eq!(witness.update(&AccountState::new(Address::repeat_byte(4))), Err(StateError::MissingTrieNode));
+    }
+
+    #[test]
+    fn removal_proof_covers_the_nodes_removal_merges() {
+        let entries = [(&b"doe"[..], &b"reindeer"[..]), (b"dog", b"puppy"), (b"dogglesworth", &[b'c'; 40])];
+        let full = trie(&entries);
+        let mut expected = full.clone();
+        expected.remove(b"doe").unwrap();
+
+        // Removing `doe` leaves `dog`'s branch alone under the root branch.
+        let mut witness = MerklePatriciaTrie::from_nodes(full.hash(), &full.prove(b"doe")).unwrap();
+        assert_eq!(witness.remove(b"doe"), Err(StateError::MissingTrieNode));
+
+        let mut witness = MerklePatriciaTrie::from_nodes(full.hash(), &full.prove_removal(b"doe")).unwrap();
+        witness.remove(b"doe").unwrap();
+        assert_eq!(witness.hash(), expected.hash());
+
+        let mut state = StateTrie::from_accounts(&accounts());
+        let removed = Address::repeat_byte(5);
+        let mut witness = StateTrie::open(state.root(), state.prove_removal(&removed)).unwrap();
+        for state in [&mut state, &mut witness] {
+            state.remove(&removed).unwrap();
+        }
+        assert_eq!(witness.root(), state.root());
+        let rest: Vec<_> = accounts().into_iter().filter(|a| a.address != removed).collect();
+        assert_eq!(state.root(), StateTrie::from_accounts(&rest).root());
+    }
+
+    #[test]
+    fn full_node_set_reopens_the_trie() {
+        let full = StateTrie::from_accounts(&accounts());
+        let reopened = StateTrie::open(full.root(), full.trie.nodes()).unwrap();
+
+        for account in accounts() {
+            assert_eq!(reopened.get(&account.address), Ok(Some(account)));
+        }
+    }
+
+    #[test]
+    fn nodes_must_rebuild_the_claimed_root() {
+        // A leaf whose even-length path flag carries a stray nibble decodes,
+        // but re-encodes to a different node.
+        let mut payload = Vec::new();
+        [0x2f, 0x12].as_slice().encode(&mut payload);
+        [0x42; 40].as_slice().encode(&mut payload);
+        let mut node = Vec::new();
+        Header { list: true, payload_length: payload.len() }.encode(&mut node);
+        node.extend_from_slice(&payload);
+        let root = keccak256(&node);
+
+        let error = MerklePatriciaTrie::from_nodes(root, &[Bytes::from(node)]).unwrap_err();
+        assert!(matches!(error, StateError::WitnessRootMismatch { claimed, .. } if claimed == root));
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, B256, U256};
+use alloy_sol_types::{sol, SolValue};
+
+use crate::StateTransitionProof;
+
+sol! {
+    /// Public values as `ZKEVMRollup.hashPublicValues` lays them out with `abi.encode`.
+    struct PublicValues {
+        uint256 chainId;
+        bytes32 oldStateRoot;
+        bytes32 newStateRoot;
+        uint256 batchIndex;
+        uint256 transactionCount;
+        uint256 gasUsed;
+        uint256 baseFee;
+        uint256 nextBaseFee;
+        bytes32 transactionsRoot;
+        bytes32 receiptsRoot;
+        bytes32 outcomesRoot;
+    }
+}
+
+impl StateTransitionProof {
+    /// Bytes the guest commits. Every field is static, so this is exactly the
+    /// `abi.encode(...)` the contract hashes.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        PublicValues {
+            chainId: U256::from(self.chain_id),
+            oldStateRoot: self.old_state_root,
+            newStateRoot: self.new_state_root,
+            batchIndex: U256::from(self.batch_index),
+            transactionCount: U256::from(self.transaction_count),
+            gasUsed: U256::from(self.gas_used),
+            baseFee: U256::from(self.base_fee),
+            nextBaseFee: U256::from(self.next_base_fee),
+            transactionsRoot: self.transactions_root,
+            receiptsRoot: self.receipts_root,
+            outcomesRoot: self.outcomes_root,
+        }
+        .abi_encode()
+    }
+
+    /// Inverse of `abi_encode`, for hosts reading the public values of a proof.
+    /// `None` unless `bytes` encode `PublicValues` with every integer in a `u64`.
+    pub fn abi_decode(bytes: &[u8]) -> Option<Self> {
+        let values = PublicValues::abi_decode(bytes, true).ok()?;
+        Some(Self {
+            chain_id: values.chainId.try_into().ok()?,
+            old_state_root: values.oldStateRoot,
+            new_state_root: values.newStateRoot,
+            batch_index: values.batchIndex.try_into().ok()?,
+            transaction_count: values.transactionCount.try_into().ok()?,
+            gas_used: values.gasUsed.try_into().ok()?,
+            base_fee: values.baseFee.try_into().ok()?,
+            next_base_fee: values.nextBaseFee.try_into().ok()?,
+            transactions_root: values.transactionsRoot,
+            receipts_root: values.receiptsRoot,
+            outcomes_root: values.outcomesRoot,
+        })
+    }
+
+    /// Hash the verifier is called with by `ZKEVMRollup.submitBatch`.
+    pub fn public_values_hash(&self) -> B256 {
+        keccak256(self.abi_encode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    use crate::tx_root;
+
+    fn proof() -> StateTransitionProof {
+        StateTransitionProof {
+            chain_id: 31337,
+            old_state_root: B256::repeat_byte(0x11),
+            new_state_root: B256::repeat_byte(0x22),
+            batch_index: 7,
+            transaction_count: 2,
+            gas_used: 42_000,
+            base_fee: 1_000_000_000,
+            next_base_fee: 875_350_000,
+            transactions_root: tx_root::compute_root(&[B256::repeat_byte(0xaa), B256::repeat_byte(0xbb)]),
+            receipts_root: B256::repeat_byte(0x33),
+            outcomes_root: B256::repeat_byte(0x44),
+        }
+    }
+
+    /// Word-by-word transcription of `ZKEVMRollup.hashPublicValues`.
+    fn contract_hash_public_values(proof: &StateTransitionProof) -> (Vec<u8>, B256) {
+        let words = [
+            U256::from(proof.chain_id).to_be_bytes::<32>(),
+            proof.old_state_root.0,
+            proof.new_state_root.0,
+            U256::from(proof.batch_index).to_be_bytes::<32>(),
+            U256::from(proof.transaction_count).to_be_bytes::<32>(),
+            U256::from(proof.gas_used).to_be_bytes::<32>(),
+            U256::from(proof.base_fee).to_be_bytes::<32>(),
+            U256::from(proof.next_base_fee).to_be_bytes::<32>(),
+            proof.transactions_root.0,
+            proof.receipts_root.0,
+            proof.outcomes_root.0,
+        ];
+        let encoded = words.concat();
+        let hash = keccak256(&encoded);
+        (encoded, hash)
+    }
+
+    #[test]
+    fn encoding_matches_contract() {
+        let proof = proof();
+        let (encoded, hash) = contract_hash_public_values(&proof);
+
+        assert_eq!(proof.abi_encode(), encoded);
+        assert_eq!(proof.public_values_hash(), hash);
+    }
+
+    #[test]
+    fn encoding_matches_contract_for_empty_batch() {
+        let proof = StateTransitionProof {
+            transaction_count: 0,
+            transactions_root: tx_root::compute_root(&[]),
+            receipts_root: crate::receipt::receipts_root(&[]),
+            outcomes_root: crate::receipt::outcomes_root(&[]),
+            ..proof()
+        };
+        let (encoded, hash) = contract_hash_public_values(&proof);
+
+        assert_eq!(proof.abi_encode(), encoded);
+        assert_eq!(proof.public_values_hash(), hash);
+    }
+
+    #[test]
+    fn decoding_inverts_encoding() {
+        let (encoded, _) = contract_hash_public_values(&proof());
+
+        let decoded = StateTransitionProof::abi_decode(&encoded).unwrap();
+        assert_eq!(decoded.abi_encode(), encoded);
+        assert_eq!(decoded.batch_index, 7);
+
+        assert!(StateTransitionProof::abi_decode(&encoded[..encoded.len() - 1]).is_none());
+        let mut too_large = encoded;
+        too_large[0] = 1;
+        assert!(StateTransitionProof::abi_decode(&too_large).is_none());
+    }
+
+    /// Same vector as the `hashPublicValues` test in `contracts/test`.
+    #[test]
+    fn public_values_hash_vector() {
+        let proof = proof();
+
+        assert_eq!(
+            proof.transactions_root,
+            b256!("9f89faaf1495298300ca41edde79c5cc9cb9bf17e1c9ef97acfdc53194f901e1")
+        );
+        assert_eq!(
+            proof.public_values_hash(),
+            b256!("26065cc2e13d3be3a0b47ae35390c9a966a3a3883c5f556eda0e4f20c16fd7aa")
+        );
+    }
+}
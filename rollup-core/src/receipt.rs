@@ -0,0 +1,292 @@
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, Address, Bloom, BloomInput, Bytes, B256};
+use alloy_rlp::{BufMut, Encodable, Header};
+use alloy_sol_types::{sol, SolValue};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, TransactionError};
+use crate::mpt::MerklePatriciaTrie;
+use crate::tx_root;
+
+/// Outcome of a transaction within its batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TransactionStatus {
+    Success = 0,
+    /// Included: the nonce was bumped and gas was paid, but the transfer did not happen.
+    Failed = 1,
+    /// Not executable at all; the state is left untouched.
+    Rejected = 2,
+}
+
+sol! {
+    /// Leaf of the outcomes root: `keccak256(abi.encode(status, errorCode))`.
+    struct OutcomeLeaf {
+        uint8 status;
+        uint16 errorCode;
+    }
+}
+
+/// Event emitted during execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Log {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+/// Receipt of a transaction. The Ethereum fields (status, cumulative gas, logs
+/// bloom and logs) are committed by `receipts_root`; the full status, which
+/// tells failed from rejected transactions, and the error code by
+/// `outcomes_root`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub status: TransactionStatus,
+    pub error: Option<TransactionError>,
+    pub gas_used: u64,
+    /// EIP-2718 type of the transaction, set by `apply_batch`.
+    pub tx_type: u8,
+    /// Gas used by the batch up to and including this transaction, set by `apply_batch`.
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<Log>,
+}
+
+impl TransactionReceipt {
+    pub fn success(gas_used: u64) -> Self {
+        Self::new(TransactionStatus::Success, None, gas_used)
+    }
+
+    pub fn failed(error: TransactionError, gas_used: u64) -> Self {
+        Self::new(TransactionStatus::Failed, Some(error), gas_used)
+    }
+
+    pub fn rejected(error: TransactionError) -> Self {
+        Self::new(TransactionStatus::Rejected, Some(error), 0)
+    }
+
+    fn new(status: TransactionStatus, error: Option<TransactionError>, gas_used: u64) -> Self {
+        Self { status, error, gas_used, tx_type: 0, cumulative_gas_used: 0, logs: Vec::new() }
+    }
+
+    /// `0` for successful transactions.
+    pub fn error_code(&self) -> u16 {
+        self.error.map_or(0, |error| error.code() as u16)
+    }
+
+    /// Leaf of `outcomes_root` for this transaction.
+    pub fn outcome_hash(&self) -> B256 {
+        let leaf = OutcomeLeaf { status: self.status as u8, errorCode: self.error_code() };
+        keccak256(leaf.abi_encode())
+    }
+
+    pub fn logs_bloom(&self) -> Bloom {
+        let mut bloom = Bloom::ZERO;
+        for log in &self.logs {
+            bloom.accrue(BloomInput::Raw(log.address.as_slice()));
+            for topic in &log.topics {
+                bloom.accrue(BloomInput::Raw(topic.as_slice()));
+            }
+        }
+        bloom
+    }
+
+    fn payload_length(&self) -> usize {
+        self.succeeded().length() + self.cumulative_gas_used.length() + Bloom::ZERO.length() + self.logs.length()
+    }
+
+    /// Post-Byzantium status: `1` on success, `0` for failed and rejected transactions.
+    fn succeeded(&self) -> bool {
+        self.status == TransactionStatus::Success
+    }
+}
+
+impl Encodable for Log {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.address.encode(out);
+        self.topics.encode(out);
+        self.data.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Log {
+    fn payload_length(&self) -> usize {
+        self.address.length() + self.topics.length() + self.data.length()
+    }
+}
+
+/// EIP-2718 encoding as stored in the receipts trie: the bare RLP list
+/// `[status, cumulative_gas_used, logs_bloom, logs]` for legacy transactions,
+/// prefixed with the transaction type otherwise.
+impl Encodable for TransactionReceipt {
+    fn encode(&self, out: &mut dyn BufMut) {
+        if self.tx_type != 0 {
+            out.put_u8(self.tx_type);
+        }
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.succeeded().encode(out);
+        self.cumulative_gas_used.encode(out);
+        self.logs_bloom().encode(out);
+        self.logs.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        let type_length = (self.tx_type != 0) as usize;
+        type_length + payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+/// Every transaction of a batch that did not succeed, with its position.
+pub fn transaction_errors(receipts: &[TransactionReceipt]) -> Vec<Error> {
+    receipts
+        .iter()
+        .enumerate()
+        .filter_map(|(index, receipt)| receipt.error.map(|error| Error::Transaction { index, error }))
+        .collect()
+}
+
+/// Ethereum receipts root: a Merkle Patricia Trie mapping `rlp(index)` to the
+/// encoded receipt of every transaction of the batch, rejected ones included.
+pub fn receipts_root(receipts: &[TransactionReceipt]) -> B256 {
+    receipts_trie(receipts).hash()
+}
+
+/// Commitment to the status and error code of every transaction, in the same
+/// tree as the transactions root so the same positions and inclusion proofs
+/// apply. Ethereum receipts give a rejected transaction status 0 like a failed
+/// one; this is what shows its nonce was never consumed.
+pub fn outcomes_root(receipts: &[TransactionReceipt]) -> B256 {
+    let hashes: Vec<B256> = receipts.iter().map(TransactionReceipt::outcome_hash).collect();
+    tx_root::compute_root(&hashes)
+}
+
+/// Trie nodes proving the receipt at `index` against `receipts_root(receipts)`.
+/// Verify by opening them with `MerklePatriciaTrie::from_nodes` and looking up
+/// `rlp(index)`.
+pub fn prove(receipts: &[TransactionReceipt], index: usize) -> Vec<Bytes> {
+    receipts_trie(receipts).prove(&alloy_rlp::encode(index))
+}
+
+fn receipts_trie(receipts: &[TransactionReceipt]) -> MerklePatriciaTrie {
+    let mut trie = MerklePatriciaTrie::new();
+    for (index, receipt) in receipts.iter().enumerate() {
+        trie.insert(&alloy_rlp::encode(index), alloy_rlp::encode(receipt))
+            .expect("a fully built trie has no missing nodes");
+    }
+    trie
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, keccak256, U256};
+
+    fn receipt(receipt: TransactionReceipt, tx_type: u8, cumulative_gas_used: u64) -> TransactionReceipt {
+        TransactionReceipt { tx_type, cumulative_gas_used, ..receipt }
+    }
+
+    #[test]
+    fn receipt_encoding_vectors() {
+        let success = receipt(TransactionReceipt::success(21_000), 2, 21_000);
+        let error = TransactionError::InsufficientBalanceForValue { balance: U256::from(9_000), value: U256::from(10_000) };
+        let failed = receipt(TransactionReceipt::failed(error, 21_000), 2, 42_000);
+
+        let encoded = alloy_rlp::encode(&success);
+        assert_eq!(encoded.len(), success.length());
+        assert_eq!(&encoded[..7], &[0x02, 0xf9, 0x01, 0x08, 0x01, 0x82, 0x52]);
+        assert_eq!(keccak256(&encoded), b256!("0dc61ea76a6acc34e0dba04ff43ad00ab0c936efe8399ba09c4975ee8c0f0079"));
+        assert_eq!(
+            receipts_root(&[success, failed]),
+            b256!("4f6a54c5f9f549156ff9d63d24c9afcb5e3e79df99db6670aeebc80e2adca0f2")
+        );
+        assert_eq!(receipts_root(&[]), crate::mpt::EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn outcomes_tell_rejected_from_failed() {
+        let failed = TransactionReceipt::failed(TransactionError::ExecutionReverted, 21_000);
+        let rejected = TransactionReceipt::rejected(TransactionError::NonceTooLow { expected: 1, actual: 0 });
+
+        // Both carry status 0 in their Ethereum receipts.
+        assert_eq!(
+            receipts_root(&[receipt(failed.clone(), 2, 21_000)]),
+            receipts_root(&[receipt(TransactionReceipt::rejected(TransactionError::ExecutionReverted), 2, 21_000)])
+        );
+        assert_ne!(failed.outcome_hash(), rejected.outcome_hash());
+
+        let mut encoded = [0u8; 64];
+        encoded[31] = TransactionStatus::Rejected as u8;
+        encoded[62..].copy_from_slice(&5u16.to_be_bytes());
+        assert_eq!(rejected.outcome_hash(), keccak256(encoded));
+        assert_eq!(
+            outcomes_root(&[failed.clone(), rejected.clone()]),
+            tx_root::compute_root(&[failed.outcome_hash(), rejected.outcome_hash()])
+        );
+        assert_eq!(outcomes_root(&[]), B256::ZERO);
+    }
+
+    #[test]
+    fn logs_are_encoded_and_accrued_in_the_bloom() {
+        let log = Log {
+            address: Address::repeat_byte(0xaa),
+            topics: vec![B256::repeat_byte(0x11)],
+            data: Bytes::from_static(&[0x01, 0x02]),
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![log.clone()],
+            ..receipt(TransactionReceipt::success(30_000), 0, 30_000)
+        };
+
+        let bloom = receipt.logs_bloom();
+        assert!(bloom.contains_input(BloomInput::Raw(log.address.as_slice())));
+        assert!(bloom.contains_input(BloomInput::Raw(log.topics[0].as_slice())));
+        assert_eq!(bloom.iter().map(|byte| byte.count_ones()).sum::<u32>(), 6);
+        assert_eq!(
+            keccak256(alloy_rlp::encode(&receipt)),
+            b256!("6b7cc8a0b3ab5678eb57c3470ed38f05034657367762a89ef972dc8cb48b00e2")
+        );
+    }
+
+    #[test]
+    fn every_receipt_has_a_valid_proof() {
+        let receipts: Vec<_> = (1..=20u64)
+            .map(|n| receipt(TransactionReceipt::success(21_000), 2, n * 21_000))
+            .collect();
+        let root = receipts_root(&receipts);
+
+        for (index, expected) in receipts.iter().enumerate() {
+            let proof = prove(&receipts, index);
+            let trie = MerklePatriciaTrie::from_nodes(root, &proof).unwrap();
+            let encoded = trie.get(&alloy_rlp::encode(index)).unwrap().unwrap();
+            assert_eq!(encoded, alloy_rlp::encode(expected).as_slice());
+        }
+    }
+
+    #[test]
+    fn rejected_receipts_use_no_gas() {
+        let receipt = TransactionReceipt::rejected(TransactionError::NonceTooLow { expected: 3, actual: 2 });
+
+        assert_eq!(receipt.gas_used, 0);
+        assert_eq!(receipt.error_code(), 5);
+        assert!(!receipt.succeeded());
+        assert_eq!(TransactionReceipt::success(21_000).error_code(), 0);
+    }
+
+    #[test]
+    fn errors_are_located_in_the_batch() {
+        let error = TransactionError::NonceTooHigh { expected: 0, actual: 4 };
+        let receipts = [TransactionReceipt::success(21_000), TransactionReceipt::rejected(error)];
+
+        let errors = transaction_errors(&receipts);
+
+        assert_eq!(errors, vec![Error::Transaction { index: 1, error }]);
+        assert_eq!(errors[0].to_string(), "transaction 1: nonce 4 too high, expected 0 (error 6)");
+    }
+}
@@ -0,0 +1,347 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, Address, B256};
+use alloy_rlp::Encodable;
+use serde::{Deserialize, Serialize};
+
+use crate::error::StateError;
+use crate::state::StateWitness;
+use crate::AccountState;
+
+/// Number of levels between the root and the leaves; one per bit of `keccak256(address)`.
+pub const TREE_DEPTH: usize = 256;
+
+/// Nodes are addressed by their depth and the key bits leading to them, with all
+/// bits below that depth cleared. Empty subtrees are not stored.
+type NodeMap = BTreeMap<(usize, B256), B256>;
+
+/// Membership (`account` is `Some`) or non-membership (`account` is `None`) proof
+/// for a single account, with siblings ordered from the leaf level up to the root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountProof {
+    pub address: Address,
+    pub account: Option<AccountState>,
+    pub siblings: Vec<B256>,
+}
+
+impl AccountProof {
+    pub fn compute_root(&self) -> B256 {
+        let key = keccak256(self.address);
+        let mut node = leaf_hash(self.account.as_ref());
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            let depth = TREE_DEPTH - 1 - level;
+            node = if bit(&key, depth) {
+                hash_pair(*sibling, node)
+            } else {
+                hash_pair(node, *sibling)
+            };
+        }
+        node
+    }
+
+    // `Option::is_none_or` is newer than the SP1 guest toolchain.
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn verify(&self, root: B256) -> bool {
+        self.siblings.len() == TREE_DEPTH
+            && self.account.as_ref().map_or(true, |a| a.address == self.address)
+            && self.compute_root() == root
+    }
+}
+
+/// Full sparse Merkle tree over every account in the state, used natively to
+/// compute roots and produce proofs for the accounts a batch touches.
+#[derive(Debug, Clone, Default)]
+pub struct SparseMerkleTree {
+    nodes: NodeMap,
+    accounts: BTreeMap<Address, AccountState>,
+}
+
+impl SparseMerkleTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_accounts(accounts: &[AccountState]) -> Self {
+        let mut tree = Self::new();
+        for account in accounts {
+            tree.insert(account.clone());
+        }
+        tree
+    }
+
+    pub fn root(&self) -> B256 {
+        self.nodes.get(&(0, B256::ZERO)).copied().unwrap_or(B256::ZERO)
+    }
+
+    pub fn get(&self, address: &Address) -> Option<&AccountState> {
+        self.accounts.get(address)
+    }
+
+    pub fn insert(&mut self, account: AccountState) {
+        update_path(&mut self.nodes, keccak256(account.address), leaf_hash(Some(&account)));
+        self.accounts.insert(account.address, account);
+    }
+
+    pub fn prove(&self, address: Address) -> AccountProof {
+        let key = keccak256(address);
+        let siblings = (0..TREE_DEPTH)
+            .rev()
+            .map(|depth| sibling(&self.nodes, &key, depth))
+            .collect();
+
+        AccountProof {
+            address,
+            account: self.accounts.get(&address).cloned(),
+            siblings,
+        }
+    }
+}
+
+/// The part of the tree covered by a set of account proofs. This is all the guest
+/// sees of the state: only witnessed accounts can be read or updated, which is
+/// enough to recompute the root after the batch has been applied.
+#[derive(Debug, Clone)]
+pub struct SparseMerkleWitness {
+    root: B256,
+    nodes: NodeMap,
+    accounts: BTreeMap<Address, Option<AccountState>>,
+}
+
+impl StateWitness for SparseMerkleWitness {
+    type Proof = Vec<AccountProof>;
+
+    fn open(root: B256, proofs: Self::Proof) -> Result<Self, StateError> {
+        let mut nodes = NodeMap::new();
+        let mut accounts = BTreeMap::new();
+
+        for proof in proofs {
+            if !proof.verify(root) {
+                return Err(StateError::InvalidAccountProof { address: proof.address });
+            }
+
+            let key = keccak256(proof.address);
+            let mut node = leaf_hash(proof.account.as_ref());
+            set_node(&mut nodes, TREE_DEPTH, key, node);
+            for (level, sibling) in proof.siblings.iter().enumerate() {
+                let depth = TREE_DEPTH - 1 - level;
+                set_node(&mut nodes, depth + 1, flip(&prefix(&key, depth + 1), depth), *sibling);
+                node = if bit(&key, depth) {
+                    hash_pair(*sibling, node)
+                } else {
+                    hash_pair(node, *sibling)
+                };
+                set_node(&mut nodes, depth, prefix(&key, depth), node);
+            }
+
+            accounts.insert(proof.address, proof.account);
+        }
+
+        Ok(Self { root, nodes, accounts })
+    }
+
+    fn root(&self) -> B256 {
+        self.root
+    }
+
+    fn get(&self, address: &Address) -> Result<Option<AccountState>, StateError> {
+        self.accounts
+            .get(address)
+            .cloned()
+            .ok_or(StateError::AccountNotInWitness { address: *address })
+    }
+
+    fn update(&mut self, account: &AccountState) -> Result<(), StateError> {
+        self.set(account.address, Some(account))
+    }
+
+    fn remove(&mut self, address: &Address) -> Result<(), StateError> {
+        self.set(*address, None)
+    }
+}
+
+impl SparseMerkleWitness {
+    fn set(&mut self, address: Address, account: Option<&AccountState>) -> Result<(), StateError> {
+        let slot = self.accounts.get_mut(&address).ok_or(StateError::AccountNotInWitness { address })?;
+        *slot = account.cloned();
+
+        self.root = update_path(&mut self.nodes, keccak256(address), leaf_hash(account));
+        Ok(())
+    }
+}
+
+fn leaf_hash(account: Option<&AccountState>) -> B256 {
+    match account {
+        Some(account) => {
+            let mut encoded = Vec::new();
+            account.encode(&mut encoded);
+            keccak256(&encoded)
+        }
+        None => B256::ZERO,
+    }
+}
+
+/// Empty subtrees hash to zero at every level, so default nodes never need to be
+/// materialised.
+fn hash_pair(left: B256, right: B256) -> B256 {
+    if left.is_zero() && right.is_zero() {
+        return B256::ZERO;
+    }
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left.as_slice());
+    buf[32..].copy_from_slice(right.as_slice());
+    keccak256(buf)
+}
+
+fn bit(key: &B256, depth: usize) -> bool {
+    key[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+fn flip(key: &B256, depth: usize) -> B256 {
+    let mut flipped = *key;
+    flipped[depth / 8] ^= 0x80 >> (depth % 8);
+    flipped
+}
+
+// `usize::is_multiple_of` is newer than the SP1 guest toolchain.
+#[allow(clippy::manual_is_multiple_of)]
+fn prefix(key: &B256, depth: usize) -> B256 {
+    let mut prefix = B256::ZERO;
+    let full_bytes = depth / 8;
+    prefix[..full_bytes].copy_from_slice(&key[..full_bytes]);
+    if depth % 8 != 0 {
+        prefix[full_bytes] = key[full_bytes] & (0xff << (8 - depth % 8));
+    }
+    prefix
+}
+
+/// Sibling of the node at `depth + 1` on `key`'s path.
+fn sibling(nodes: &NodeMap, key: &B256, depth: usize) -> B256 {
+    let path = flip(&prefix(key, depth + 1), depth);
+    nodes.get(&(depth + 1, path)).copied().unwrap_or(B256::ZERO)
+}
+
+fn set_node(nodes: &mut NodeMap, depth: usize, path: B256, hash: B256) {
+    if hash.is_zero() {
+        nodes.remove(&(depth, path));
+    } else {
+        nodes.insert((depth, path), hash);
+    }
+}
+
+fn update_path(nodes: &mut NodeMap, key: B256, leaf: B256) -> B256 {
+    let mut node = leaf;
+    set_node(nodes, TREE_DEPTH, key, node);
+    for depth in (0..TREE_DEPTH).rev() {
+        let sibling = sibling(nodes, &key, depth);
+        node = if bit(&key, depth) {
+            hash_pair(sibling, node)
+        } else {
+            hash_pair(node, sibling)
+        };
+        set_node(nodes, depth, prefix(&key, depth), node);
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    fn account(byte: u8, balance: u64) -> AccountState {
+        AccountState { balance: U256::from(balance), ..AccountState::new(Address::repeat_byte(byte)) }
+    }
+
+    fn accounts() -> Vec<AccountState> {
+        vec![account(1, 10), account(2, 20), account(3, 30)]
+    }
+
+    #[test]
+    fn root_vectors() {
+        assert_eq!(SparseMerkleTree::new().root(), B256::ZERO);
+
+        // A lone leaf is hashed with an empty sibling at every level.
+        let single = account(1, 10);
+        let key = keccak256(single.address);
+        let root = (0..TREE_DEPTH).rev().fold(leaf_hash(Some(&single)), |node, depth| {
+            let mut buf = [0u8; 64];
+            let offset = if bit(&key, depth) { 32 } else { 0 };
+            buf[offset..offset + 32].copy_from_slice(node.as_slice());
+            keccak256(buf)
+        });
+        assert_eq!(SparseMerkleTree::from_accounts(&[single]).root(), root);
+
+        // Insertion order does not matter.
+        let mut reversed = accounts();
+        reversed.reverse();
+        assert_eq!(SparseMerkleTree::from_accounts(&reversed).root(), SparseMerkleTree::from_accounts(&accounts()).root());
+    }
+
+    #[test]
+    fn membership_and_non_membership_proofs_verify() {
+        let tree = SparseMerkleTree::from_accounts(&accounts());
+        let root = tree.root();
+
+        for account in accounts() {
+            let proof = tree.prove(account.address);
+            assert_eq!(proof.account.as_ref(), Some(&account));
+            assert!(proof.verify(root));
+        }
+
+        let absent = tree.prove(Address::repeat_byte(4));
+        assert_eq!(absent.account, None);
+        assert!(absent.verify(root));
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let tree = SparseMerkleTree::from_accounts(&accounts());
+        let root = tree.root();
+        let proof = tree.prove(Address::repeat_byte(1));
+
+        let mut siblings = proof.siblings.clone();
+        siblings[TREE_DEPTH - 1] = B256::repeat_byte(0xff);
+        assert!(!AccountProof { siblings, ..proof.clone() }.verify(root));
+
+        assert!(!AccountProof { account: Some(account(1, 11)), ..proof.clone() }.verify(root));
+        assert!(!AccountProof { account: None, ..proof.clone() }.verify(root));
+        assert!(!AccountProof { address: Address::repeat_byte(2), ..proof.clone() }.verify(root));
+        assert!(!AccountProof { siblings: proof.siblings[1..].to_vec(), ..proof.clone() }.verify(root));
+
+        let absent = tree.prove(Address::repeat_byte(4));
+        assert!(!AccountProof { account: Some(AccountState::new(absent.address)), ..absent.clone() }.verify(root));
+
+        let address = proof.address;
+        assert_eq!(
+            SparseMerkleWitness::open(root, vec![AccountProof { account: Some(account(1, 11)), ..proof }]).unwrap_err(),
+            StateError::InvalidAccountProof { address }
+        );
+    }
+
+    #[test]
+    fn witness_updates_match_full_tree() {
+        let tree = SparseMerkleTree::from_accounts(&accounts());
+        let covered = [1, 3, 4].map(Address::repeat_byte);
+        let proofs = covered.iter().map(|address| tree.prove(*address)).collect();
+        let mut witness = SparseMerkleWitness::open(tree.root(), proofs).unwrap();
+
+        assert_eq!(witness.get(&Address::repeat_byte(1)), Ok(Some(account(1, 10))));
+        assert_eq!(witness.get(&Address::repeat_byte(4)), Ok(None));
+        assert_eq!(
+            witness.get(&Address::repeat_byte(2)),
+            Err(StateError::AccountNotInWitness { address: Address::repeat_byte(2) })
+        );
+
+        witness.update(&account(1, 5)).unwrap();
+        witness.update(&account(4, 40)).unwrap();
+        witness.remove(&Address::repeat_byte(3)).unwrap();
+        let expected = SparseMerkleTree::from_accounts(&[account(1, 5), account(2, 20), account(4, 40)]);
+        assert_eq!(witness.root(), expected.root());
+
+        assert_eq!(
+            witness.update(&account(2, 0)),
+            Err(StateError::AccountNotInWitness { address: Address::repeat_byte(2) })
+        );
+    }
+}
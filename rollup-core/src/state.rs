@@ -0,0 +1,34 @@
+use alloy_primitives::{Address, B256};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::StateError;
+use crate::AccountState;
+
+/// Authenticated view of the pre-state handed to the guest alongside a batch.
+///
+/// Reads and writes are only possible for accounts the proof covers, so the
+/// root after applying the batch is fully determined by the witness.
+pub trait StateWitness: Sized {
+    type Proof: Serialize + DeserializeOwned;
+
+    fn open(root: B256, proof: Self::Proof) -> Result<Self, StateError>;
+
+    fn root(&self) -> B256;
+
+    /// `Ok(None)` means the witness proves the account does not exist.
+    fn get(&self, address: &Address) -> Result<Option<AccountState>, StateError>;
+
+    fn update(&mut self, account: &AccountState) -> Result<(), StateError>;
+
+    /// Deletes the account at `address`, as Ethereum does with destroyed
+    /// accounts and touched empty ones (EIP-161).
+    fn remove(&mut self, address: &Address) -> Result<(), StateError>;
+}
+
+/// State commitment used by the guest.
+#[cfg(not(feature = "smt-state"))]
+pub type State = crate::mpt::StateTrie;
+
+#[cfg(feature = "smt-state")]
+pub type State = crate::smt::SparseMerkleWitness;
@@ -0,0 +1,157 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+use serde::{Deserialize, Serialize};
+
+use crate::error::StateError;
+use crate::mpt::MerklePatriciaTrie;
+
+/// Storage trie nodes of one account, proving the slots a batch accesses
+/// against the account's pre-state `storage_root`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageProof {
+    pub address: Address,
+    pub nodes: Vec<Bytes>,
+}
+
+/// Storage of one account: a trie mapping `keccak256(slot)` to the RLP of every
+/// non-zero value. Its root is the account's `storage_root`.
+#[derive(Debug, Clone, Default)]
+pub struct StorageTrie {
+    trie: MerklePatriciaTrie,
+}
+
+impl StorageTrie {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuilds the part of the storage under `root` covered by `nodes`.
+    pub fn open(root: B256, nodes: &[Bytes]) -> Result<Self, StateError> {
+        Ok(Self { trie: MerklePatriciaTrie::from_nodes(root, nodes)? })
+    }
+
+    pub fn from_slots(slots: &[(U256, U256)]) -> Self {
+        let mut storage = Self::new();
+        for &(slot, value) in slots {
+            storage.set(slot, value).expect("a fully built trie has no missing nodes");
+        }
+        storage
+    }
+
+    pub fn root(&self) -> B256 {
+        self.trie.hash()
+    }
+
+    /// Slots never written read as zero.
+    pub fn get(&self, slot: U256) -> Result<U256, StateError> {
+        match self.trie.get(key(slot).as_slice())? {
+            Some(mut encoded) => U256::decode(&mut encoded).map_err(|_| StateError::InvalidTrieNode),
+            None => Ok(U256::ZERO),
+        }
+    }
+
+    /// Setting a slot to zero removes it from the trie, as in Ethereum.
+    pub fn set(&mut self, slot: U256, value: U256) -> Result<(), StateError> {
+        if value.is_zero() {
+            self.trie.remove(key(slot).as_slice())
+        } else {
+            self.trie.insert(key(slot).as_slice(), alloy_rlp::encode(value))
+        }
+    }
+
+    /// Nodes needed to read and write every slot in `slots`, including
+    /// clearing those in `cleared`.
+    pub fn prove(&self, slots: &BTreeSet<U256>, cleared: &BTreeSet<U256>) -> Vec<Bytes> {
+        let mut nodes: Vec<Bytes> = slots
+            .iter()
+            .flat_map(|slot| {
+                if cleared.contains(slot) {
+                    self.trie.prove_removal(key(*slot).as_slice())
+                } else {
+                    self.trie.prove(key(*slot).as_slice())
+                }
+            })
+            .collect();
+        nodes.sort();
+        nodes.dedup();
+        nodes
+    }
+
+    /// Every node of the trie, for running a batch natively over full storage.
+    pub fn nodes(&self) -> Vec<Bytes> {
+        self.trie.nodes()
+    }
+}
+
+fn key(slot: U256) -> B256 {
+    keccak256(slot.to_be_bytes::<32>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpt::EMPTY_ROOT_HASH;
+    use alloy_primitives::b256;
+
+    fn slots(range: std::ops::Range<u64>) -> Vec<(U256, U256)> {
+        range.map(|i| (U256::from(i), U256::from(i + 1))).collect()
+    }
+
+    #[test]
+    fn storage_root_vectors() {
+        let storage = StorageTrie::from_slots(&[(U256::ZERO, U256::from(100))]);
+        assert_eq!(storage.root(), b256!("52fa3cc6870cf8c957d654cf4e972604d2a48306f2d34293950b043d54e60f0a"));
+
+        let mut storage = StorageTrie::from_slots(&[(U256::ZERO, U256::from(5)), (U256::from(1), U256::from(7))]);
+        assert_eq!(storage.root(), b256!("d18a05267db23fd76aa27821b10f00359c4a5db97a1c2ed99d4f3c90d2be6b3e"));
+        storage.set(U256::ZERO, U256::ZERO).unwrap();
+        assert_eq!(storage.root(), b256!("2e7827dc2c61c322f13f77e6f25dd18844ccc48426dde70301d2d57d138fced8"));
+        storage.set(U256::from(1), U256::ZERO).unwrap();
+        assert_eq!(storage.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn clearing_slots_matches_a_trie_built_without_them() {
+        let mut storage = StorageTrie::from_slots(&slots(0..64));
+        for slot in (0..64).step_by(2) {
+            storage.set(U256::from(slot), U256::ZERO).unwrap();
+        }
+        let odd: Vec<_> = slots(0..64).into_iter().filter(|(slot, _)| slot.bit(0)).collect();
+
+        assert_eq!(storage.root(), StorageTrie::from_slots(&odd).root());
+        assert_eq!(storage.get(U256::from(2)), Ok(U256::ZERO));
+        assert_eq!(storage.get(U256::from(3)), Ok(U256::from(4)));
+    }
+
+    #[test]
+    fn witness_covers_accessed_slots_only() {
+        let mut full = StorageTrie::from_slots(&slots(0..64));
+        let accessed: BTreeSet<U256> = [3, 7, 20, 100].map(U256::from).into();
+        let cleared: BTreeSet<U256> = [3, 20].map(U256::from).into();
+        let mut witness = StorageTrie::open(full.root(), &full.prove(&accessed, &cleared)).unwrap();
+
+        assert_eq!(witness.get(U256::from(7)), Ok(U256::from(8)));
+        assert_eq!(witness.get(U256::from(100)), Ok(U256::ZERO));
+        assert_eq!(witness.get(U256::from(40)), Err(StateError::MissingTrieNode));
+
+        for storage in [&mut full, &mut witness] {
+            storage.set(U256::from(3), U256::ZERO).unwrap();
+            storage.set(U256::from(20), U256::ZERO).unwrap();
+            storage.set(U256::from(7), U256::from(9)).unwrap();
+            storage.set(U256::from(100), U256::from(1)).unwrap();
+        }
+        assert_eq!(witness.root(), full.root());
+    }
+
+    #[test]
+    fn full_node_set_reopens_the_whole_trie() {
+        let full = StorageTrie::from_slots(&slots(0..64));
+        let reopened = StorageTrie::open(full.root(), &full.nodes()).unwrap();
+
+        assert_eq!(reopened.root(), full.root());
+        assert_eq!(reopened.get(U256::from(40)), Ok(U256::from(41)));
+    }
+}
@@ -0,0 +1,768 @@
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, uint, Address, Bytes, Signature, TxKind, B256, U256};
+use alloy_rlp::{Buf, BufMut, Decodable, Encodable, Header};
+use serde::{Deserialize, Serialize};
+
+use crate::error::TransactionError;
+
+/// EIP-2718 type byte of access-list transactions.
+pub const EIP2930_TX_TYPE: u8 = 0x01;
+/// EIP-2718 type byte of dynamic fee transactions.
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+
+/// Signatures with `s` above half the curve order are malleable and rejected (EIP-2).
+const SECP256K1N_HALF: U256 =
+    uint!(0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E73_57A4501DDFE92F46681B20A0_U256);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessListItem {
+    pub address: Address,
+    pub storage_keys: Vec<B256>,
+}
+
+/// Pre-EIP-2718 transaction, replay protected by EIP-155 when `chain_id` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxLegacy {
+    pub chain_id: Option<u64>,
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub to: TxKind,
+    pub value: U256,
+    pub input: Bytes,
+}
+
+/// EIP-2930 access-list transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxEip2930 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub to: TxKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+}
+
+/// EIP-1559 dynamic fee transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxEip1559 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u64,
+    pub max_fee_per_gas: u64,
+    pub gas_limit: u64,
+    pub to: TxKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+}
+
+/// Unsigned transaction body of any supported EIP-2718 type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypedTransaction {
+    Legacy(TxLegacy),
+    Eip2930(TxEip2930),
+    Eip1559(TxEip1559),
+}
+
+impl From<TxLegacy> for TypedTransaction {
+    fn from(tx: TxLegacy) -> Self {
+        Self::Legacy(tx)
+    }
+}
+
+impl From<TxEip2930> for TypedTransaction {
+    fn from(tx: TxEip2930) -> Self {
+        Self::Eip2930(tx)
+    }
+}
+
+impl From<TxEip1559> for TypedTransaction {
+    fn from(tx: TxEip1559) -> Self {
+        Self::Eip1559(tx)
+    }
+}
+
+/// Signed transaction as it appears in a batch. `from` is the sender claimed
+/// by the sequencer and must match the signer recovered from `signature`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub from: Address,
+    pub tx: TypedTransaction,
+    pub signature: Signature,
+}
+
+impl Encodable for AccessListItem {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.address.encode(out);
+        self.storage_keys.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl AccessListItem {
+    fn payload_length(&self) -> usize {
+        self.address.length() + self.storage_keys.length()
+    }
+}
+
+impl Decodable for AccessListItem {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = list_payload(buf)?;
+        let item = Self {
+            address: Decodable::decode(&mut payload)?,
+            storage_keys: Decodable::decode(&mut payload)?,
+        };
+        finish(payload, item)
+    }
+}
+
+impl TxLegacy {
+    fn fields_length(&self) -> usize {
+        self.nonce.length()
+            + self.gas_price.length()
+            + self.gas_limit.length()
+            + self.to.length()
+            + self.value.length()
+            + self.input.length()
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.nonce.encode(out);
+        self.gas_price.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+    }
+
+    fn decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: None,
+            nonce: Decodable::decode(buf)?,
+            gas_price: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+        })
+    }
+
+    /// EIP-155 `v`: the recovery id offset by the chain id, or 27/28 without one.
+    /// Twice a `u64` chain id does not fit a `u64`, so `v` is widened.
+    fn v(&self, y_parity: bool) -> u128 {
+        match self.chain_id {
+            Some(chain_id) => 35 + chain_id as u128 * 2 + y_parity as u128,
+            None => 27 + y_parity as u128,
+        }
+    }
+}
+
+impl TxEip2930 {
+    fn fields_length(&self) -> usize {
+        self.chain_id.length()
+            + self.nonce.length()
+            + self.gas_price.length()
+            + self.gas_limit.length()
+            + self.to.length()
+            + self.value.length()
+            + self.input.length()
+            + self.access_list.length()
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.gas_price.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.access_list.encode(out);
+    }
+
+    fn decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            gas_price: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+        })
+    }
+}
+
+impl TxEip1559 {
+    fn fields_length(&self) -> usize {
+        self.chain_id.length()
+            + self.nonce.length()
+            + self.max_priority_fee_per_gas.length()
+            + self.max_fee_per_gas.length()
+            + self.gas_limit.length()
+            + self.to.length()
+            + self.value.length()
+            + self.input.length()
+            + self.access_list.length()
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.access_list.encode(out);
+    }
+
+    fn decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            max_priority_fee_per_gas: Decodable::decode(buf)?,
+            max_fee_per_gas: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+        })
+    }
+}
+
+impl TypedTransaction {
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            Self::Legacy(_) => 0,
+            Self::Eip2930(_) => EIP2930_TX_TYPE,
+            Self::Eip1559(_) => EIP1559_TX_TYPE,
+        }
+    }
+
+    pub fn chain_id(&self) -> Option<u64> {
+        match self {
+            Self::Legacy(tx) => tx.chain_id,
+            Self::Eip2930(tx) => Some(tx.chain_id),
+            Self::Eip1559(tx) => Some(tx.chain_id),
+        }
+    }
+
+    pub fn nonce(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.nonce,
+            Self::Eip2930(tx) => tx.nonce,
+            Self::Eip1559(tx) => tx.nonce,
+        }
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.gas_limit,
+            Self::Eip2930(tx) => tx.gas_limit,
+            Self::Eip1559(tx) => tx.gas_limit,
+        }
+    }
+
+    /// Most the sender will pay per unit of gas, base fee included.
+    pub fn max_fee_per_gas(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.gas_price,
+            Self::Eip2930(tx) => tx.gas_price,
+            Self::Eip1559(tx) => tx.max_fee_per_gas,
+        }
+    }
+
+    /// Most the sender will pay the fee recipient per unit of gas on top of the
+    /// base fee. Transactions with a plain gas price tip everything above it.
+    pub fn max_priority_fee_per_gas(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.gas_price,
+            Self::Eip2930(tx) => tx.gas_price,
+            Self::Eip1559(tx) => tx.max_priority_fee_per_gas,
+        }
+    }
+
+    /// The account called, or `TxKind::Create` for a contract creation whose
+    /// input is the init code.
+    pub fn to(&self) -> TxKind {
+        match self {
+            Self::Legacy(tx) => tx.to,
+            Self::Eip2930(tx) => tx.to,
+            Self::Eip1559(tx) => tx.to,
+        }
+    }
+
+    pub fn value(&self) -> U256 {
+        match self {
+            Self::Legacy(tx) => tx.value,
+            Self::Eip2930(tx) => tx.value,
+            Self::Eip1559(tx) => tx.value,
+        }
+    }
+
+    pub fn input(&self) -> &Bytes {
+        match self {
+            Self::Legacy(tx) => &tx.input,
+            Self::Eip2930(tx) => &tx.input,
+            Self::Eip1559(tx) => &tx.input,
+        }
+    }
+
+    pub fn access_list(&self) -> &[AccessListItem] {
+        match self {
+            Self::Legacy(_) => &[],
+            Self::Eip2930(tx) => &tx.access_list,
+            Self::Eip1559(tx) => &tx.access_list,
+        }
+    }
+
+    /// Hash the sender signs, as defined by EIP-155, EIP-2930 and EIP-1559.
+    pub fn signature_hash(&self) -> B256 {
+        let mut out = Vec::new();
+        match self {
+            Self::Legacy(tx) => {
+                let mut payload_length = tx.fields_length();
+                if let Some(chain_id) = tx.chain_id {
+                    payload_length += chain_id.length() + 2;
+                }
+                Header { list: true, payload_length }.encode(&mut out);
+                tx.encode_fields(&mut out);
+                if let Some(chain_id) = tx.chain_id {
+                    chain_id.encode(&mut out);
+                    0u8.encode(&mut out);
+                    0u8.encode(&mut out);
+                }
+            }
+            Self::Eip2930(tx) => {
+                out.put_u8(EIP2930_TX_TYPE);
+                Header { list: true, payload_length: tx.fields_length() }.encode(&mut out);
+                tx.encode_fields(&mut out);
+            }
+            Self::Eip1559(tx) => {
+                out.put_u8(EIP1559_TX_TYPE);
+                Header { list: true, payload_length: tx.fields_length() }.encode(&mut out);
+                tx.encode_fields(&mut out);
+            }
+        }
+        keccak256(&out)
+    }
+
+    fn fields_length(&self) -> usize {
+        match self {
+            Self::Legacy(tx) => tx.fields_length(),
+            Self::Eip2930(tx) => tx.fields_length(),
+            Self::Eip1559(tx) => tx.fields_length(),
+        }
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(tx) => tx.encode_fields(out),
+            Self::Eip2930(tx) => tx.encode_fields(out),
+            Self::Eip1559(tx) => tx.encode_fields(out),
+        }
+    }
+
+    /// `v` for legacy transactions, the bare y parity for typed ones.
+    fn v(&self, signature: &Signature) -> u128 {
+        let y_parity = signature.v().y_parity();
+        match self {
+            Self::Legacy(tx) => tx.v(y_parity),
+            _ => y_parity as u128,
+        }
+    }
+}
+
+impl Transaction {
+    pub fn chain_id(&self) -> Option<u64> {
+        self.tx.chain_id()
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.tx.nonce()
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        self.tx.gas_limit()
+    }
+
+    pub fn max_fee_per_gas(&self) -> u64 {
+        self.tx.max_fee_per_gas()
+    }
+
+    pub fn max_priority_fee_per_gas(&self) -> u64 {
+        self.tx.max_priority_fee_per_gas()
+    }
+
+    pub fn to(&self) -> TxKind {
+        self.tx.to()
+    }
+
+    /// Address a contract creation deploys to, derived from the sender and
+    /// nonce as by `CREATE`.
+    pub fn create_address(&self) -> Option<Address> {
+        self.to().is_create().then(|| self.from.create(self.nonce()))
+    }
+
+    pub fn value(&self) -> U256 {
+        self.tx.value()
+    }
+
+    pub fn input(&self) -> &Bytes {
+        self.tx.input()
+    }
+
+    pub fn access_list(&self) -> &[AccessListItem] {
+        self.tx.access_list()
+    }
+
+    /// Recovers the signer with secp256k1 ECDSA; `ecdsa` is patched to SP1's
+    /// precompile so this stays cheap inside the zkVM.
+    pub fn recover_signer(&self) -> Result<Address, TransactionError> {
+        if self.signature.s() > SECP256K1N_HALF {
+            return Err(TransactionError::InvalidSignature);
+        }
+        self.signature
+            .recover_address_from_prehash(&self.tx.signature_hash())
+            .map_err(|_| TransactionError::InvalidSignature)
+    }
+
+    /// Signs `tx` with `key` and claims the key's address as the sender, for
+    /// tests and benchmarks; the rollup itself only recovers signers.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn sign(key: &k256::ecdsa::SigningKey, tx: impl Into<TypedTransaction>) -> Self {
+        let tx = tx.into();
+        let (signature, recovery_id) = key
+            .sign_prehash_recoverable(tx.signature_hash().as_slice())
+            .expect("signature hash is 32 bytes");
+        Self { from: Address::from_private_key(key), tx, signature: Signature::from((signature, recovery_id)) }
+    }
+
+    /// Decodes an EIP-2718 encoded transaction as submitted by a wallet and
+    /// fills in the sender from its signature.
+    pub fn decode_raw(mut raw: &[u8]) -> Result<Self, TransactionError> {
+        let mut tx = Self::decode(&mut raw).map_err(|_| TransactionError::InvalidEncoding)?;
+        if !raw.is_empty() {
+            return Err(TransactionError::InvalidEncoding);
+        }
+        tx.from = tx.recover_signer()?;
+        Ok(tx)
+    }
+
+    fn payload_length(&self) -> usize {
+        self.tx.fields_length()
+            + self.tx.v(&self.signature).length()
+            + self.signature.r().length()
+            + self.signature.s().length()
+    }
+}
+
+/// EIP-2718 encoding: the bare RLP list for legacy transactions, the type byte
+/// followed by the RLP list otherwise. Its keccak is the transaction hash.
+impl Encodable for Transaction {
+    fn encode(&self, out: &mut dyn BufMut) {
+        if self.tx.tx_type() != 0 {
+            out.put_u8(self.tx.tx_type());
+        }
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.tx.encode_fields(out);
+        self.tx.v(&self.signature).encode(out);
+        self.signature.r().encode(out);
+        self.signature.s().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        let type_length = (self.tx.tx_type() != 0) as usize;
+        type_length + payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+/// Inverse of the EIP-2718 encoding. The sender is left as the zero address
+/// since only signature recovery can establish it.
+impl Decodable for Transaction {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        // Legacy transactions have no type byte; `0x00` is not a valid one, as
+        // it would give a second encoding of the same legacy transaction.
+        let tx_type = match buf.first() {
+            Some(&byte) if byte <= 0x7f => {
+                buf.advance(1);
+                Some(byte)
+            }
+            Some(_) => None,
+            None => return Err(alloy_rlp::Error::InputTooShort),
+        };
+
+        let mut payload = list_payload(buf)?;
+        let tx = match tx_type {
+            None => TypedTransaction::Legacy(TxLegacy::decode_fields(&mut payload)?),
+            Some(EIP2930_TX_TYPE) => TypedTransaction::Eip2930(TxEip2930::decode_fields(&mut payload)?),
+            Some(EIP1559_TX_TYPE) => TypedTransaction::Eip1559(TxEip1559::decode_fields(&mut payload)?),
+            Some(_) => return Err(alloy_rlp::Error::Custom("unsupported transaction type")),
+        };
+
+        let v = u128::decode(&mut payload)?;
+        let r = U256::decode(&mut payload)?;
+        let s = U256::decode(&mut payload)?;
+
+        let (tx, y_parity) = match tx {
+            TypedTransaction::Legacy(mut legacy) => {
+                let y_parity = match v {
+                    27 | 28 => (v - 27) as u64,
+                    v if v >= 35 => {
+                        let chain_id = u64::try_from((v - 35) / 2)
+                            .map_err(|_| alloy_rlp::Error::Custom("legacy chain id out of range"))?;
+                        legacy.chain_id = Some(chain_id);
+                        ((v - 35) % 2) as u64
+                    }
+                    _ => return Err(alloy_rlp::Error::Custom("invalid legacy signature v")),
+                };
+                (TypedTransaction::Legacy(legacy), y_parity)
+            }
+            tx if v <= 1 => (tx, v as u64),
+            _ => return Err(alloy_rlp::Error::Custom("invalid signature y parity")),
+        };
+
+        let signature = Signature::from_rs_and_parity(r, s, y_parity)
+            .map_err(|_| alloy_rlp::Error::Custom("invalid signature"))?;
+        finish(payload, Self { from: Address::ZERO, tx, signature })
+    }
+}
+
+/// Consumes a list header and returns its payload, advancing `buf` past it.
+fn list_payload<'a>(buf: &mut &'a [u8]) -> alloy_rlp::Result<&'a [u8]> {
+    let header = Header::decode(buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString);
+    }
+    if buf.len() < header.payload_length {
+        return Err(alloy_rlp::Error::InputTooShort);
+    }
+    let (payload, rest) = buf.split_at(header.payload_length);
+    *buf = rest;
+    Ok(payload)
+}
+
+fn finish<T>(payload: &[u8], value: T) -> alloy_rlp::Result<T> {
+    if payload.is_empty() {
+        Ok(value)
+    } else {
+        Err(alloy_rlp::Error::ListLengthMismatch { expected: 0, got: payload.len() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    fn decode_hex(raw: &str) -> Vec<u8> {
+        hex::decode(raw).unwrap()
+    }
+
+    #[test]
+    fn eip155_legacy_vector() {
+        // Example transaction from EIP-155, signed with key 0x4646..46.
+        let raw = decode_hex("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+
+        let tx = Transaction::decode_raw(&raw).unwrap();
+        assert_eq!(
+            tx.tx,
+            TypedTransaction::Legacy(TxLegacy {
+                chain_id: Some(1),
+                nonce: 9,
+                gas_price: 20_000_000_000,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0x35)),
+                value: U256::from(1_000_000_000_000_000_000u64),
+                input: Bytes::new(),
+            })
+        );
+        assert_eq!(
+            tx.tx.signature_hash(),
+            B256::from_slice(&decode_hex("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"))
+        );
+        assert_eq!(tx.from, address!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+
+        let encoded = alloy_rlp::encode(&tx);
+        assert_eq!(encoded, raw);
+        assert_eq!(encoded.len(), tx.length());
+        assert_eq!(
+            keccak256(&encoded),
+            B256::from_slice(&decode_hex("33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"))
+        );
+    }
+
+    #[test]
+    fn eip1559_vector() {
+        let raw = decode_hex("02f8730180847735940085174876e800825208943535353535353535353535353535353535353535880de0b6b3a764000080c080a0f938691236ffc9eed1354f47ee2b2f5528b85a79fc1013f9f766d0c8fe89c5a6a006bc8ee524f816c0c9fb06ab38ed43ef32ca0c847018c7f07f731256ed5bb83a");
+
+        let tx = Transaction::decode_raw(&raw).unwrap();
+        assert_eq!(
+            tx.tx,
+            TypedTransaction::Eip1559(TxEip1559 {
+                chain_id: 1,
+                nonce: 0,
+                max_priority_fee_per_gas: 2_000_000_000,
+                max_fee_per_gas: 100_000_000_000,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0x35)),
+                value: U256::from(1_000_000_000_000_000_000u64),
+                input: Bytes::new(),
+                access_list: Vec::new(),
+            })
+        );
+        assert_eq!(
+            tx.tx.signature_hash(),
+            B256::from_slice(&decode_hex("0b90ec27784b0b819f9413e83d1eab4c84f1eda3150059f1881f867fb010f1d1"))
+        );
+        assert_eq!(tx.from, address!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"));
+
+        let encoded = alloy_rlp::encode(&tx);
+        assert_eq!(encoded, raw);
+        assert_eq!(encoded.len(), tx.length());
+        assert_eq!(
+            keccak256(&encoded),
+            B256::from_slice(&decode_hex("0c7049342a0c0254ae64b82017a22367ff74a137c3334782be5e3f9fdcf7ef6e"))
+        );
+    }
+
+    #[test]
+    fn eip2930_round_trip() {
+        let tx = Transaction {
+            from: Address::ZERO,
+            tx: TypedTransaction::Eip2930(TxEip2930 {
+                chain_id: 5,
+                nonce: 3,
+                gas_price: 7,
+                gas_limit: 60_000,
+                to: TxKind::Call(Address::repeat_byte(0x22)),
+                value: U256::from(5),
+                input: Bytes::from(vec![0x00, 0x01, 0xff]),
+                access_list: vec![AccessListItem {
+                    address: Address::repeat_byte(0x33),
+                    storage_keys: vec![B256::ZERO, B256::repeat_byte(0x01)],
+                }],
+            }),
+            signature: Signature::from_rs_and_parity(U256::from(1), U256::from(2), 1u64).unwrap(),
+        };
+
+        let encoded = alloy_rlp::encode(&tx);
+        assert_eq!(encoded[0], EIP2930_TX_TYPE);
+        assert_eq!(encoded.len(), tx.length());
+        assert_eq!(Transaction::decode(&mut encoded.as_slice()).unwrap(), tx);
+    }
+
+    #[test]
+    fn contract_creation_round_trip() {
+        let tx = Transaction {
+            from: Address::ZERO,
+            tx: TypedTransaction::Eip1559(TxEip1559 {
+                chain_id: 1,
+                nonce: 0,
+                max_priority_fee_per_gas: 1,
+                max_fee_per_gas: 1,
+                gas_limit: 100_000,
+                to: TxKind::Create,
+                value: U256::ZERO,
+                input: Bytes::from(vec![0x60, 0x00, 0x60, 0x00, 0xf3]),
+                access_list: Vec::new(),
+            }),
+            signature: Signature::from_rs_and_parity(U256::from(1), U256::from(2), 0u64).unwrap(),
+        };
+
+        let encoded = alloy_rlp::encode(&tx);
+        // Chain id, nonce, fees and gas limit, then the empty string for `to`.
+        assert_eq!(&encoded[..11], &[0x02, 0xd4, 0x01, 0x80, 0x01, 0x01, 0x83, 0x01, 0x86, 0xa0, 0x80]);
+        assert_eq!(encoded.len(), tx.length());
+        assert_eq!(Transaction::decode(&mut encoded.as_slice()).unwrap(), tx);
+    }
+
+    #[test]
+    fn legacy_v_covers_every_chain_id() {
+        let tx = Transaction {
+            from: Address::ZERO,
+            tx: TypedTransaction::Legacy(TxLegacy {
+                chain_id: Some(u64::MAX),
+                nonce: 0,
+                gas_price: 1,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0x35)),
+                value: U256::ZERO,
+                input: Bytes::new(),
+            }),
+            signature: Signature::from_rs_and_parity(U256::from(1), U256::from(2), 1u64).unwrap(),
+        };
+
+        assert_eq!(tx.tx.v(&tx.signature), 35 + 2 * u64::MAX as u128 + 1);
+        let encoded = alloy_rlp::encode(&tx);
+        assert_eq!(encoded.len(), tx.length());
+        assert_eq!(Transaction::decode(&mut encoded.as_slice()).unwrap(), tx);
+    }
+
+    #[test]
+    fn legacy_v_beyond_u64_chain_id_is_rejected() {
+        let legacy = TxLegacy {
+            chain_id: None,
+            nonce: 0,
+            gas_price: 1,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::repeat_byte(0x35)),
+            value: U256::ZERO,
+            input: Bytes::new(),
+        };
+        let v = 35 + 2 * (u64::MAX as u128 + 1);
+        let (r, s) = (U256::from(1), U256::from(2));
+
+        let mut raw = Vec::new();
+        let payload_length = legacy.fields_length() + v.length() + r.length() + s.length();
+        Header { list: true, payload_length }.encode(&mut raw);
+        legacy.encode_fields(&mut raw);
+        v.encode(&mut raw);
+        r.encode(&mut raw);
+        s.encode(&mut raw);
+
+        assert_eq!(
+            Transaction::decode(&mut raw.as_slice()),
+            Err(alloy_rlp::Error::Custom("legacy chain id out of range"))
+        );
+    }
+
+    #[test]
+    fn legacy_with_zero_type_byte_is_rejected() {
+        let raw = decode_hex("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+        let prefixed = [&[0x00][..], &raw].concat();
+
+        assert!(Transaction::decode_raw(&raw).is_ok());
+        assert_eq!(
+            Transaction::decode(&mut prefixed.as_slice()),
+            Err(alloy_rlp::Error::Custom("unsupported transaction type"))
+        );
+        assert_eq!(Transaction::decode_raw(&prefixed), Err(TransactionError::InvalidEncoding));
+    }
+
+    #[test]
+    fn high_s_signature_is_rejected() {
+        let raw = decode_hex("f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+        let mut tx = Transaction::decode_raw(&raw).unwrap();
+
+        let order = uint!(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141_U256);
+        let y_parity = !tx.signature.v().y_parity() as u64;
+        tx.signature = Signature::from_rs_and_parity(tx.signature.r(), order - tx.signature.s(), y_parity).unwrap();
+
+        assert_eq!(tx.recover_signer(), Err(TransactionError::InvalidSignature));
+    }
+}
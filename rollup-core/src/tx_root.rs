@@ -0,0 +1,145 @@
+use alloc::vec::Vec;
+
+use alloy_primitives::{keccak256, B256};
+use serde::{Deserialize, Serialize};
+
+/// Binary keccak Merkle tree over per-transaction hashes of a batch, in batch order.
+///
+/// Leaves are padded with zero hashes up to the next power of two, and an empty
+/// batch commits to the zero hash. The transaction count is committed alongside
+/// the root, which rules out proving a padding leaf.
+pub fn compute_root(leaves: &[B256]) -> B256 {
+    if leaves.is_empty() {
+        return B256::ZERO;
+    }
+    let mut level = padded(leaves);
+    while level.len() > 1 {
+        level = parent_level(&level);
+    }
+    level[0]
+}
+
+/// Proof that the transaction hash at `index` is part of `compute_root(leaves)`.
+pub fn prove(leaves: &[B256], index: usize) -> Option<InclusionProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut level = padded(leaves);
+    let mut position = index;
+    let mut siblings = Vec::new();
+    while level.len() > 1 {
+        siblings.push(level[position ^ 1]);
+        level = parent_level(&level);
+        position /= 2;
+    }
+
+    Some(InclusionProof { index: index as u64, siblings })
+}
+
+/// Siblings are ordered from the leaf level up to the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub index: u64,
+    pub siblings: Vec<B256>,
+}
+
+impl InclusionProof {
+    pub fn compute_root(&self, leaf: B256) -> B256 {
+        let mut node = leaf;
+        let mut position = self.index;
+        for sibling in &self.siblings {
+            node = if position & 1 == 1 {
+                hash_pair(*sibling, node)
+            } else {
+                hash_pair(node, *sibling)
+            };
+            position >>= 1;
+        }
+        node
+    }
+
+    /// Mirrors `ZKEVMRollup.verifyTransactionInclusion`.
+    pub fn verify(&self, root: B256, transaction_count: u64, leaf: B256) -> bool {
+        self.index < transaction_count
+            && self.siblings.len() == depth(transaction_count)
+            && self.compute_root(leaf) == root
+    }
+}
+
+fn depth(leaf_count: u64) -> usize {
+    leaf_count.next_power_of_two().trailing_zeros() as usize
+}
+
+fn padded(leaves: &[B256]) -> Vec<B256> {
+    let mut level = leaves.to_vec();
+    level.resize(leaves.len().next_power_of_two(), B256::ZERO);
+    level
+}
+
+fn parent_level(level: &[B256]) -> Vec<B256> {
+    level.chunks(2).map(|pair| hash_pair(pair[0], pair[1])).collect()
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left.as_slice());
+    buf[32..].copy_from_slice(right.as_slice());
+    keccak256(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    fn leaves(count: u8) -> Vec<B256> {
+        (1..=count).map(B256::repeat_byte).collect()
+    }
+
+    #[test]
+    fn root_vectors() {
+        assert_eq!(compute_root(&[]), B256::ZERO);
+        assert_eq!(compute_root(&[B256::repeat_byte(0xaa)]), B256::repeat_byte(0xaa));
+        assert_eq!(
+            compute_root(&[B256::repeat_byte(0xaa), B256::repeat_byte(0xbb), B256::repeat_byte(0xcc)]),
+            b256!("d1370056138aa7c7187e8a108bbf1a481efad21e528c7dee7437657e698074c6")
+        );
+    }
+
+    #[test]
+    fn every_transaction_has_a_valid_proof() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let root = compute_root(&leaves);
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = prove(&leaves, index).unwrap();
+                assert!(proof.verify(root, count as u64, *leaf));
+            }
+            assert!(prove(&leaves, leaves.len()).is_none());
+        }
+    }
+
+    #[test]
+    fn proof_for_wrong_leaf_or_position_is_rejected() {
+        let leaves = leaves(5);
+        let root = compute_root(&leaves);
+        let proof = prove(&leaves, 2).unwrap();
+
+        assert!(!proof.verify(root, 5, leaves[3]));
+        assert!(!InclusionProof { index: 3, ..proof.clone() }.verify(root, 5, leaves[2]));
+        assert!(!proof.verify(root, 4, leaves[2]));
+    }
+
+    #[test]
+    fn padding_leaf_cannot_be_proven() {
+        let leaves = leaves(5);
+        let root = compute_root(&leaves);
+        let mut padded = leaves.clone();
+        padded.resize(8, B256::ZERO);
+        let proof = prove(&padded, 6).unwrap();
+
+        assert_eq!(proof.compute_root(B256::ZERO), root);
+        assert!(!proof.verify(root, 5, B256::ZERO));
+    }
+}
@@ -0,0 +1,13 @@
+use sp1_build::{build_program_with_args, BuildArgs};
+
+fn main() {
+    build_program_with_args("../sp1-guest", BuildArgs::default());
+    build_program_with_args(
+        "../sp1-guest",
+        BuildArgs {
+            features: vec!["json-input".to_string()],
+            elf_name: "zk-evm-rollup-guest-json-input".to_string(),
+            ..Default::default()
+        },
+    );
+}
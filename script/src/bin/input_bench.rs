@@ -0,0 +1,95 @@
+//! Compares zkVM cycles spent by the guest on JSON and binary input for
+//! synthetic batches of transfers.
+//!
+//! ```sh
+//! cargo run --release --bin input_bench
+//! ```
+
+use alloy_primitives::{Address, Bytes, TxKind, B256, U256};
+use k256::ecdsa::SigningKey;
+use sp1_sdk::{ProverClient, SP1Stdin};
+use zk_evm_rollup_core::evm::ContractWitness;
+use zk_evm_rollup_core::input::GuestInput;
+use zk_evm_rollup_core::mpt::StateTrie;
+use zk_evm_rollup_core::state::StateWitness;
+use zk_evm_rollup_core::transaction::{Transaction, TxEip1559};
+use zk_evm_rollup_core::{apply_batch, touched_addresses, AccountState, StateTransition};
+
+const BINARY_ELF: &[u8] = include_bytes!("../../../sp1-guest/elf/riscv32im-succinct-zkvm-elf");
+const JSON_ELF: &[u8] = include_bytes!("../../../sp1-guest/elf/zk-evm-rollup-guest-json-input");
+
+const BATCH_SIZES: [usize; 3] = [10, 100, 1000];
+const CHAIN_ID: u64 = 1;
+
+/// One funded sender paying `size` fresh recipients, with the witness and
+/// post-state root the guest expects.
+fn batch(size: usize) -> GuestInput {
+    let key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+    let sender = Address::from_private_key(&key);
+    let genesis = StateTrie::from_accounts(&[AccountState {
+        balance: U256::from(u64::MAX),
+        ..AccountState::new(sender)
+    }]);
+
+    let transactions = (0..size as u64)
+        .map(|nonce| {
+            let tx = TxEip1559 {
+                chain_id: CHAIN_ID,
+                nonce,
+                max_priority_fee_per_gas: 1,
+                max_fee_per_gas: 1,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::from_word(B256::from(U256::from(nonce + 1)))),
+                value: U256::from(1_000),
+                input: Bytes::new(),
+                access_list: Vec::new(),
+            };
+            Transaction::sign(&key, tx)
+        })
+        .collect();
+
+    let mut transition = StateTransition {
+        chain_id: CHAIN_ID,
+        transactions,
+        old_state_root: genesis.root(),
+        new_state_root: B256::ZERO,
+        batch_index: 0,
+        fee_recipient: Address::repeat_byte(0xfe),
+        base_fee: 0,
+    };
+
+    let mut witness: Vec<Bytes> = touched_addresses(&transition).iter().flat_map(|a| genesis.prove(a)).collect();
+    witness.sort();
+    witness.dedup();
+
+    let mut post = StateTrie::open(transition.old_state_root, witness.clone()).unwrap();
+    apply_batch(&transition, &mut post, &ContractWitness::default()).unwrap();
+    transition.new_state_root = post.root();
+
+    GuestInput { transition, witness, contracts: ContractWitness::default() }
+}
+
+fn main() {
+    sp1_sdk::utils::setup_logger();
+    let client = ProverClient::new();
+
+    println!("{:>6}  {:>6}  {:>12}  {:>14}  {:>14}", "txs", "format", "input bytes", "read cycles", "total cycles");
+    for size in BATCH_SIZES {
+        let input = batch(size);
+        for (format, elf, bytes) in [("json", JSON_ELF, input.to_json()), ("binary", BINARY_ELF, input.to_binary())] {
+            let mut stdin = SP1Stdin::new();
+            stdin.write_vec(bytes.clone());
+
+            let (_, report) = client.execute(elf, stdin).run().expect("guest execution failed");
+            let read_cycles = report.cycle_tracker.get("read-input").copied().unwrap_or_default();
+            println!(
+                "{:>6}  {:>6}  {:>12}  {:>14}  {:>14}",
+                size,
+                format,
+                bytes.len(),
+                read_cycles,
+                report.total_instruction_count()
+            );
+        }
+    }
+}
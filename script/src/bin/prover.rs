@@ -0,0 +1,201 @@
+//! Host prover for the rollup guest: executes batches in the zkVM, proves them
+//! and verifies the proofs.
+//!
+//! ```sh
+//! cargo run --release --bin prover -- execute --batch batch.json
+//! cargo run --release --bin prover -- prove --batch batch.json --mode groth16 --out proofs/0
+//! cargo run --release --bin prover -- verify --proof proofs/0
+//! ```
+//!
+//! A batch file is a `GuestInput` as JSON (`GuestInput::to_json`): the
+//! transition with its claimed post-state root, the state witness and the
+//! contract witness. The guest is handed it in the binary encoding.
+//!
+//! `prove` writes three files to its output directory:
+//!
+//! - `proof.bin`: the `SP1ProofWithPublicValues` as saved by `sp1-sdk`
+//!   (bincode), which `verify` loads.
+//! - `public_values.bin`: the bytes the guest committed, `abi.encode` of the
+//!   `PublicValues` struct that `ZKEVMRollup.hashPublicValues` hashes.
+//! - `proof.json`: a `ProofFile` for tooling outside Rust, with the mode, the
+//!   verifying key hash, the public values both raw and decoded, and for
+//!   Groth16 and PLONK the proof bytes `ZKEVMRollup.submitBatch` takes.
+//!
+//! `SP1_PROVER` selects local, network or mock proving as usual for `sp1-sdk`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::{Bytes, B256};
+use anyhow::{ensure, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin};
+use zk_evm_rollup_core::input::GuestInput;
+use zk_evm_rollup_core::{execute_batch, StateTransitionProof};
+
+const GUEST_ELF: &[u8] = include_bytes!("../../../sp1-guest/elf/riscv32im-succinct-zkvm-elf");
+
+const PROOF_FILE: &str = "proof.bin";
+const PUBLIC_VALUES_FILE: &str = "public_values.bin";
+const SUMMARY_FILE: &str = "proof.json";
+
+#[derive(Parser)]
+#[command(about = "Execute, prove and verify rollup batches with SP1")]
+struct Cli {
+    /// Guest ELF to load instead of the one built with this binary.
+    #[arg(long, global = true)]
+    elf: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the guest without proving and report cycles and public values.
+    Execute {
+        #[arg(long)]
+        batch: PathBuf,
+    },
+    /// Prove a batch and write the proof and its public values to `out`.
+    Prove {
+        #[arg(long)]
+        batch: PathBuf,
+        #[arg(long, value_enum, default_value_t = Mode::Compressed)]
+        mode: Mode,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Verify a proof directory written by `prove`.
+    Verify {
+        #[arg(long)]
+        proof: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    /// A STARK proof per shard; fastest to produce, largest to store.
+    Core,
+    /// Shard proofs recursively compressed into one constant-size STARK.
+    Compressed,
+    /// Compressed proof wrapped in Groth16, verifiable on chain.
+    Groth16,
+    /// Compressed proof wrapped in PLONK, verifiable on chain.
+    Plonk,
+}
+
+/// Contents of `proof.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct ProofFile {
+    mode: Mode,
+    sp1_version: String,
+    /// Verifying key hash the on-chain verifier is called with.
+    vkey_hash: String,
+    public_values: Bytes,
+    /// `ZKEVMRollup.hashPublicValues` of `state_transition`.
+    public_values_hash: B256,
+    state_transition: StateTransitionProof,
+    /// Proof bytes for `ZKEVMRollup.submitBatch`, for Groth16 and PLONK only.
+    proof: Option<Bytes>,
+}
+
+fn main() -> Result<()> {
+    sp1_sdk::utils::setup_logger();
+    let cli = Cli::parse();
+    let elf = match &cli.elf {
+        Some(path) => fs::read(path).with_context(|| format!("reading {}", path.display()))?,
+        None => GUEST_ELF.to_vec(),
+    };
+    let client = ProverClient::new();
+
+    match cli.command {
+        Command::Execute { batch } => {
+            let input = read_batch(&batch)?;
+            let expected = execute_batch(input.clone()).context("native execution failed")?;
+
+            let (public_values, report) = client.execute(&elf, stdin(&input)).run()?;
+            ensure!(
+                public_values.as_slice() == expected.abi_encode(),
+                "zkVM and native execution committed different public values"
+            );
+
+            println!("cycles: {}", report.total_instruction_count());
+            println!("{}", serde_json::to_string_pretty(&expected)?);
+        }
+        Command::Prove { batch, mode, out } => {
+            let input = read_batch(&batch)?;
+            // A batch the guest would reject fails here, before any proving work.
+            let expected = execute_batch(input.clone()).context("native execution failed")?;
+
+            let (pk, vk) = client.setup(&elf);
+            let prover = client.prove(&pk, stdin(&input));
+            let proof = match mode {
+                Mode::Core => prover.core().run(),
+                Mode::Compressed => prover.compressed().run(),
+                Mode::Groth16 => prover.groth16().run(),
+                Mode::Plonk => prover.plonk().run(),
+            }?;
+            ensure!(
+                proof.public_values.as_slice() == expected.abi_encode(),
+                "zkVM and native execution committed different public values"
+            );
+
+            write_proof(&out, mode, vk.bytes32(), &proof)?;
+            println!("wrote {mode:?} proof to {}", out.display());
+        }
+        Command::Verify { proof: dir } => {
+            let proof = SP1ProofWithPublicValues::load(dir.join(PROOF_FILE))?;
+            let public_values = fs::read(dir.join(PUBLIC_VALUES_FILE))?;
+            ensure!(
+                proof.public_values.as_slice() == public_values,
+                "{PUBLIC_VALUES_FILE} does not match the public values of the proof"
+            );
+
+            let (_, vk) = client.setup(&elf);
+            client.verify(&proof, &vk)?;
+
+            println!("proof verified");
+            println!("{}", serde_json::to_string_pretty(&decode(&public_values)?)?);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_batch(path: &Path) -> Result<GuestInput> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    GuestInput::from_json(&bytes).with_context(|| format!("decoding {}", path.display()))
+}
+
+/// The guest reads its input in the binary encoding.
+fn stdin(input: &GuestInput) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write_vec(input.to_binary());
+    stdin
+}
+
+fn decode(public_values: &[u8]) -> Result<StateTransitionProof> {
+    StateTransitionProof::abi_decode(public_values).context("public values do not encode a state transition")
+}
+
+fn write_proof(dir: &Path, mode: Mode, vkey_hash: String, proof: &SP1ProofWithPublicValues) -> Result<()> {
+    let public_values = proof.public_values.to_vec();
+    let state_transition = decode(&public_values)?;
+    let summary = ProofFile {
+        mode,
+        sp1_version: proof.sp1_version.clone(),
+        vkey_hash,
+        public_values: Bytes::from(public_values.clone()),
+        public_values_hash: state_transition.public_values_hash(),
+        state_transition,
+        proof: matches!(mode, Mode::Groth16 | Mode::Plonk).then(|| Bytes::from(proof.bytes())),
+    };
+
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    proof.save(dir.join(PROOF_FILE))?;
+    fs::write(dir.join(PUBLIC_VALUES_FILE), &public_values)?;
+    fs::write(dir.join(SUMMARY_FILE), serde_json::to_vec_pretty(&summary)?)?;
+    Ok(())
+}
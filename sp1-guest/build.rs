@@ -1,3 +0,0 @@
-fn main() {
-    sp1_build::build_client("../../sp1-guest");
-}
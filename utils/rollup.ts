@@ -10,6 +10,7 @@ import {
   bridgeToL2,
   bridgeToL1
 } from '../lib/rollup-api';
+import { batchInputPath, batchProofDir, defaultWorkDir } from '../prover/src/prover';
 
 let rollupInstance: ZKRollup | null = null;
 
@@ -94,7 +95,7 @@ export async function getBatchInfo(
       batchId,
       batchIndex,
       status: batch.finalized ? 'finalized' : 'pending',
-      transactions: batch.transactionHashes.length,
+      transactions: Number(batch.transactionCount),
       gasUsed: 123456789,
       timestamp: Number(batch.timestamp),
       blockNumber: 1234567,
@@ -111,19 +112,9 @@ export async function generateZKProofForBatch(
 ): Promise<Record<string, any>> {
   try {
     const batchIndex = parseInt(batchId.split('_')[1]);
-    const transactions = [
-      {
-        from: '0x1234567890123456789012345678901234567890',
-        to: '0x0987654321098765432109876543210987654321',
-        value: '1000000000000000000',
-        data: '0x',
-        nonce: 0,
-        gasLimit: 21000,
-        gasPrice: 1000000000,
-      },
-    ];
-
-    const proof = await generateZKProof(transactions, batchIndex);
+    const workDir = defaultWorkDir();
+
+    const proof = await generateZKProof(batchInputPath(workDir, batchIndex), batchProofDir(workDir, batchIndex));
 
     return {
       proofId: proof.proofId,
@@ -150,25 +141,19 @@ export async function verifyZKProof(
       throw new Error('Rollup not initialized');
     }
 
-    const mockProofData = '0x' + '0'.repeat(2048);
-    const mockPublicValues = {
-      oldStateRoot: '0x' + '0'.repeat(64),
-      newStateRoot: '0x' + '1'.repeat(64),
-      batchIndex: 0,
-      transactionCount: 1,
-      transactionHashes: [],
-    };
+    // Proof ids are `proof_<batchIndex>_<public values hash prefix>`.
+    const batchIndex = parseInt(proofId.split('_')[1]);
+    const startTime = Date.now();
 
     const isValid = await verifyZKProofOnChain(
       rollupInstance,
-      mockProofData,
-      mockPublicValues
+      batchProofDir(defaultWorkDir(), batchIndex)
     );
 
     return {
       proofId,
       verificationStatus: isValid ? 'verified' : 'failed',
-      verificationTime: 500,
+      verificationTime: Date.now() - startTime,
       timestamp: Date.now(),
     };
   } catch (error) {